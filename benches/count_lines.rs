@@ -0,0 +1,87 @@
+// Run with `cargo bench`. Compares the streaming classifier with the old
+// approach of collecting every line into a Vec and removing blank and
+// comment lines from it one by one. Both count the single generated file,
+// neither walks a directory.
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use plc::{count_lines, CodeStats, Params};
+
+const RUNS: u32 = 3;
+
+fn generate(dir: &Path, megabytes: usize) -> PathBuf {
+    let chunk = "\
+/// Docs for the generated function
+fn generated(x: usize) -> usize {
+    // Some comment in the body
+    let y = x * 2; // and a trailing one
+
+    /* Block comment */
+    y + 1
+}
+
+";
+    let path = dir.join(format!("generated_{}mb.rs", megabytes));
+    let content = chunk.repeat(megabytes * 1024 * 1024 / chunk.len());
+    fs::write(&path, content).expect("Failed to write generated file");
+    path
+}
+
+// What `count_lines` did before it was streaming
+fn collect_and_remove(path: &Path) -> usize {
+    let file_str = fs::read_to_string(path).unwrap();
+    let mut lines = file_str.lines().collect::<Vec<&str>>();
+
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i].trim();
+        if line.is_empty() || line.starts_with("//") || line.starts_with("/*") {
+            lines.remove(i);
+            continue;
+        }
+        i += 1;
+    }
+
+    lines.len()
+}
+
+fn time<F: FnMut() -> usize>(mut f: F) -> (Duration, usize) {
+    let mut loc = 0;
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        loc = f();
+    }
+    (start.elapsed() / RUNS, loc)
+}
+
+fn main() {
+    let dir = env::temp_dir().join("plc_bench");
+    fs::create_dir_all(&dir).expect("Failed to create bench dir");
+
+    println!(
+        "{:>6}  {:>10}  {:>18}  {:>10}",
+        "size", "lines", "collect and remove", "streaming"
+    );
+    for megabytes in [1, 2, 4] {
+        let path = generate(&dir, megabytes);
+        let lines = fs::read_to_string(&path).unwrap().lines().count();
+
+        let (old, old_loc) = time(|| collect_and_remove(&path));
+        let params = Params::new(dir.clone());
+        let (new, new_loc) = time(|| {
+            let mut stats = CodeStats::new();
+            count_lines(&path, &params, &mut stats);
+            stats.loc()
+        });
+        assert_eq!(old_loc, new_loc);
+
+        println!(
+            "{:>4}MB  {:>10}  {:>18.2?}  {:>10.2?}",
+            megabytes, lines, old, new
+        );
+        fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,12 @@
+--[[
+    Some multi line comment
+]]
+--- Some docs for function
+local function greet(name)
+    -- Some comment
+    return "Hello, " .. name
+end
+
+print(greet("Lua"))
+-- 4 lines without comments
+-- 10 with comments, 11 with comments and docs
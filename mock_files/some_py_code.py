@@ -0,0 +1,13 @@
+# Some comment
+import sys
+
+# Some explanation to function
+def fib(n):
+    if n < 2:
+        return n
+    return fib(n - 1) + fib(n - 2)
+
+
+print(fib(int(sys.argv[1])))
+# 6 lines without comments
+# 10 with comments. No docs
@@ -0,0 +1,12 @@
+/* C block comments /* do not nest */
+int main() {
+    foo(); /* x */ bar();
+    /* a */ int code = 1;
+    /* comment */ /* another comment */
+    char *s = "/* not a comment */";
+}
+/*
+/* not nested
+*/
+int code_again;
+// 6 lines without comments
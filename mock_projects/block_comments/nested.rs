@@ -0,0 +1,14 @@
+/* Rust block comments /* nest */
+   so this line is still a comment */
+fn main() {
+    foo(); /* x */ bar();
+    /* a */ let code = 1;
+    /* comment */
+    let s = "/* not a comment */";
+}
+/*
+/* nested
+*/
+struct StillComment;
+*/
+// 5 lines without comments
@@ -0,0 +1,3 @@
+fn main() {
+    println!("{}", categories::mul(2, 3));
+}
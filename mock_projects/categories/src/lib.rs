@@ -0,0 +1,33 @@
+pub fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[test]
+fn adds() {
+    assert_eq!(add(1, 2), 3);
+}
+
+pub fn sub(a: i32, b: i32) -> i32 {
+    a - b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Comments in tests are test lines too
+    // TODO: cover overflow
+    #[test]
+    fn subs() {
+        let text = "} braces in strings do not end the module {";
+        assert_eq!(sub(3, 2), 1);
+        assert!(!text.is_empty());
+    }
+}
+
+#[cfg(test)]
+use std::fmt;
+
+pub fn mul(a: i32, b: i32) -> i32 {
+    a * b
+}
@@ -0,0 +1,7 @@
+use categories::add;
+
+// TODO: test the other operations
+#[test]
+fn adds() {
+    assert_eq!(add(2, 2), 4);
+}
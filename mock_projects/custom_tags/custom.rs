@@ -0,0 +1,8 @@
+// HACK: works around the borrow checker
+// TODOS and autoFIXME are not tags
+fn main() {
+    // SAFETY: the pointer is never null
+    let x = 1; // XXX: magic number
+    // NOTE(bob): keep in sync with the docs
+    println!("{}", x); // TODO: HACK remove
+}
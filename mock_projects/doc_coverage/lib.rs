@@ -0,0 +1,48 @@
+//! Crate docs do not document the first item
+
+pub mod util;
+
+pub struct Undocumented;
+
+/// A point on the plane
+#[derive(Debug, Clone)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    /// Creates a point at the origin
+    pub fn origin() -> Self {
+        Self { x: 0, y: 0 }
+    }
+
+    pub fn is_origin(&self) -> bool {
+        self.x == 0 && self.y == 0
+    }
+
+    // Private items are left out
+    fn private(&self) {}
+}
+
+#[doc = "Docs given with an attribute"]
+pub const ORIGIN: Point = Point { x: 0, y: 0 };
+
+pub(crate) fn internal() {}
+
+/**
+ * Block doc comment
+ */
+pub enum Shape {
+    Circle,
+}
+
+/// Example with code, which is no public item itself
+pub const EXAMPLE: &str = "
+pub fn in_string() {}
+";
+
+/// The same in a raw string
+pub const RAW_EXAMPLE: &str = r#"
+pub struct InRawString;
+"#;
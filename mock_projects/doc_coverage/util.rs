@@ -0,0 +1,6 @@
+pub static mut COUNTER: usize = 0;
+
+/// Adds one
+pub unsafe fn increment() {
+    COUNTER += 1;
+}
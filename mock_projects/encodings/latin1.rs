@@ -0,0 +1,4 @@
+// caf au lait
+fn main() {
+    let s = "nave";
+}
@@ -0,0 +1,90 @@
+struct Counter {
+    count: usize,
+}
+
+impl Counter {
+    pub fn new() -> Self {
+        Self { count: 0 }
+    }
+
+    fn add(&mut self, values: &[usize]) {
+        // fn in a comment { is not a function
+        let text = "fn in a string { either";
+        self.count += values.iter().filter(|value| **value > 1).count();
+        self.count += text.len();
+    }
+}
+
+trait Describe {
+    fn name(&self) -> String;
+
+    fn lengths(&self) -> [u8; 2];
+
+    fn describe(&self) -> String {
+        format!("{}!", self.name())
+    }
+}
+
+impl Describe for Counter {
+    fn name(&self) -> String {
+        let kind = |count: usize| if count > 1 || count == 0 { "many" } else { "one" };
+        kind(self.count).to_string()
+    }
+
+    fn lengths(&self) -> [u8; 2] {
+        [0; 2]
+    }
+}
+
+impl Counter { // used for the total
+    fn total(&self) -> usize {
+        self.count
+    }
+
+    fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+}
+
+impl<T> std::fmt::Display
+    for Wrapper<T>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "wrapper")
+    }
+}
+
+struct Wrapper<T>(T);
+
+fn main() {
+    let mut counter = Counter::new();
+    counter.add(&[1, 2, 3]);
+    let print = move || println!("{}", counter.describe());
+    print();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts() {
+        let counter = Counter::new();
+        assert_eq!(counter.count, 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics() {
+        let values = vec![1, 2]
+            .into_iter()
+            .map(
+                |value| value * 2,
+            )
+            .collect::<Vec<i32>>();
+        match values[0] {
+            1 | 2 => {}
+            _ => panic!(),
+        }
+    }
+}
@@ -0,0 +1,5 @@
+// Looks like Rust items to a naive recognizer
+static int counter;
+struct point { int x; int y; };
+union value { int i; float f; };
+enum color { RED, GREEN };
@@ -0,0 +1,71 @@
+use std::fmt;
+
+pub(crate) fn visible_in_crate() {}
+
+pub(in crate::items) const unsafe fn scoped() {}
+
+pub async unsafe fn background() {}
+
+#[derive(Debug)]
+pub enum Shape {
+    Circle,
+    Square,
+}
+
+pub struct Point {
+    x: i32,
+    y: i32,
+}
+
+pub trait Area {
+    fn area(&self) -> f64;
+}
+
+pub type Points = Vec<Point>;
+
+#[repr(C)]
+union Bits {
+    int: u32,
+    float: f32,
+}
+
+mod inner {
+    pub static NAME: &str = "inner";
+}
+
+const LIMIT: usize = 10;
+static mut COUNTER: usize = 0;
+
+extern "C" {
+    fn abs(input: i32) -> i32;
+}
+
+unsafe impl Send for Point {}
+
+impl<T> Area for Vec<T> {
+    fn area(&self) -> f64 {
+        let union = 0.0;
+        let pointer: fn() = visible_in_crate;
+        union
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.x, self.y)
+    }
+}
+
+macro_rules! square {
+    ($x:expr) => {
+        $x * $x
+    };
+}
+
+// Code in a fixture is no item of this file
+const FIXTURE: &str = r#"
+#![cfg(test)]
+fn main() {
+    pub struct Fake;
+}
+"#;
@@ -0,0 +1,25 @@
+// Comment markers inside of literals are not comments
+fn literals() {
+    let url = "http://example.com";
+    let raw = r#"/* not a comment */"#;
+    let bytes = b"// still a string";
+    let raw_bytes = br##"
+/* spans
+// several lines
+"##;
+    let slash = '/';
+    let quote = '"';
+    let escaped = '\'';
+}
+
+/// Lifetimes are not char literals
+fn lifetime<'a>(s: &'a str) -> &'a str {
+    s // trailing comment
+}
+
+/*
+ * Real block comment
+ */
+struct Done;
+// 16 lines without comments
+// 22 with comments, 23 with comments and docs
@@ -0,0 +1,7 @@
+// Adds one
+fn add_one(x: i32) -> i32 {
+    /* Block
+       comment */
+
+    x + 1 // trailing
+}
\ No newline at end of file
@@ -0,0 +1,3 @@
+mod child;
+
+pub fn a() {}
@@ -0,0 +1 @@
+fn in_multi_line_string() {}
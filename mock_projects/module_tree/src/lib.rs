@@ -0,0 +1,15 @@
+pub mod a;
+mod b;
+
+#[path = "other/c_impl.rs"]
+mod c;
+
+mod inline {
+    mod nested;
+}
+
+// mod commented;
+const TEXT: &str = "mod in_string;";
+const MULTI_LINE: &str = "
+mod in_multi_line_string;
+";
@@ -0,0 +1,2 @@
+// Backup which is not compiled
+fn stray() {}
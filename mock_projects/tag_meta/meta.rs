@@ -0,0 +1,7 @@
+// TODO(alice, #1234, 2020-03-01): drop the legacy parser
+// TODO(@bob, 2999-01-01): revisit after the rewrite
+fn main() {
+    let x = 1; // FIXME(alice, PLC-42): overflow on 32 bit
+    // TODO: no metadata at all
+    // TODO(carol, https://example.com/issues/7, 2021-02-30): odd date
+}
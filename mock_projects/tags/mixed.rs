@@ -0,0 +1,11 @@
+// TODO: split into modules
+fn main() {
+    let todo_list = vec!["TODO"]; // FIXME: use a set
+    let x = 1; // TODO: remove
+    /* TODO: inline */ let y = 2;
+    let z = x + y; /// not really a doc
+    println!("{:?} {}", todo_list, z);
+}
+
+/// FIXME this is documented
+struct Holder;
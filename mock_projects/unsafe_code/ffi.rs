@@ -0,0 +1,28 @@
+use std::ptr;
+
+pub unsafe trait Zeroable {}
+
+unsafe impl Zeroable for u32 {}
+
+pub unsafe fn read(pointer: *const u32) -> u32 {
+    *pointer
+}
+
+pub const unsafe extern "C" fn callback() {}
+
+pub fn first(values: &[u32]) -> u32 {
+    // unsafe { in a comment }
+    let text = "unsafe { in a string }";
+    let value = unsafe { read(values.as_ptr()) };
+    value + text.len() as u32
+}
+
+pub fn copy(from: &[u32], to: &mut [u32]) {
+    unsafe {
+        // Comments are no lines of code
+        ptr::copy_nonoverlapping(from.as_ptr(), to.as_mut_ptr(), from.len());
+        if from.is_empty() {
+            return;
+        }
+    }
+}
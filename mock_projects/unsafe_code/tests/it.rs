@@ -0,0 +1,8 @@
+use std::ptr;
+
+#[test]
+fn reads_through_a_pointer() {
+    let x = 1;
+    let y = unsafe { ptr::read(&x) };
+    assert_eq!(y, 1);
+}
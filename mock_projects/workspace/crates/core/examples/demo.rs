@@ -0,0 +1,3 @@
+fn main() {
+    core::inc(1);
+}
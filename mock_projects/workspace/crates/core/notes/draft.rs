@@ -0,0 +1,2 @@
+// Not part of any target
+fn unused() {}
@@ -0,0 +1,4 @@
+/// Adds one
+pub fn inc(x: i32) -> i32 {
+    x + 1
+}
@@ -0,0 +1,4 @@
+#[test]
+fn inc() {
+    assert_eq!(core::inc(1), 2);
+}
@@ -1,6 +1,7 @@
-use std::path::PathBuf;
+use std::{env, path::PathBuf};
 
 use clap::Parser;
+use plc::{default_threads, Params, SortBy};
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 pub struct Args {
@@ -22,18 +23,116 @@ pub struct Args {
     #[arg(short = 'c', long = "comments")]
     pub comments: bool,
 
+    #[arg(short = 'b', long = "blanks")]
+    pub blanks: bool,
+
     #[arg(short = 'f', long = "fixme")]
     pub fixme: bool,
 
     #[arg(short = 't', long = "todo")]
     pub todo: bool,
 
+    #[arg(long = "list-tags")]
+    pub list_tags: bool,
+
+    #[arg(long = "tag", value_name = "NAME")]
+    pub tags: Vec<String>,
+
+    #[arg(long = "stale", value_name = "DAYS")]
+    pub stale: Option<u64>,
+
+    #[arg(long = "owners")]
+    pub owners: bool,
+
     #[arg(short = 'u', long = "units")]
     pub units: bool,
 
+    #[arg(long = "doc-coverage")]
+    pub doc_coverage: bool,
+
+    #[arg(long = "categories")]
+    pub categories: bool,
+
+    #[arg(long = "exclude-tests")]
+    pub exclude_tests: bool,
+
+    #[arg(long = "workspace")]
+    pub workspace: bool,
+
+    #[arg(long = "module-tree")]
+    pub module_tree: bool,
+
+    #[arg(long = "unsafe")]
+    pub unsafe_code: bool,
+
     #[arg(short = 'r', long = "ratio")]
     pub ratio: bool,
 
     #[arg(short = 'j', long = "json")]
     pub json: bool,
+
+    #[arg(short = 'l', long = "languages")]
+    pub languages: bool,
+
+    #[arg(long = "tree")]
+    pub tree: bool,
+
+    #[arg(long = "depth", requires = "tree")]
+    pub depth: Option<usize>,
+
+    #[arg(long = "files")]
+    pub files: bool,
+
+    #[arg(long = "sort", value_enum, default_value_t = SortBy::Path)]
+    pub sort: SortBy,
+
+    #[arg(long = "threads")]
+    pub threads: Option<usize>,
+}
+
+impl From<Args> for Params {
+    fn from(value: Args) -> Self {
+        let extensions: Vec<String> = match value.extensions {
+            Some(extensions) => extensions,
+            None => vec![".rs".to_string()],
+        };
+
+        let path = match value.path {
+            Some(p) => p,
+            None => env::current_dir().expect("Provided path is invalid"),
+        };
+
+        let mut params = Self {
+            extensions,
+            path,
+            verbose: value.verbose,
+            hidden: value.hidden,
+            docs: value.docs,
+            comments: value.comments,
+            blanks: value.blanks,
+            fixme: value.fixme,
+            todo: value.todo,
+            list_tags: value.list_tags,
+            stale: value.stale,
+            owners: value.owners,
+            units: value.units,
+            doc_coverage: value.doc_coverage,
+            categories: value.categories,
+            exclude_tests: value.exclude_tests,
+            workspace: value.workspace,
+            module_tree: value.module_tree,
+            unsafe_code: value.unsafe_code,
+            ratio: value.ratio,
+            json: value.json,
+            languages: value.languages,
+            tree: value.tree,
+            depth: value.depth,
+            files: value.files,
+            sort: value.sort,
+            threads: value.threads.unwrap_or_else(default_threads).max(1),
+            ..Params::new(PathBuf::new())
+        };
+        params.add_tags(value.tags);
+        params
+    }
 }
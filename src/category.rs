@@ -0,0 +1,71 @@
+use std::{ops::AddAssign, path::Path};
+
+// What code is written for. Files get theirs from the directories they
+// are in, `#[cfg(test)]` items and `#[test]` functions are test code
+// wherever they are.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    #[default]
+    Production,
+    Test,
+    Example,
+    Bench,
+}
+
+impl Category {
+    // Only directories below `root` are looked at, so pointing plc at
+    // a `tests` directory does not turn everything into test code
+    pub fn from_path(path: &Path, root: &Path) -> Self {
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        let dirs = relative.parent().into_iter().flat_map(Path::components);
+
+        for dir in dirs {
+            match dir.as_os_str().to_str() {
+                Some("tests") => return Category::Test,
+                Some("examples") => return Category::Example,
+                Some("benches") => return Category::Bench,
+                _ => {}
+            }
+        }
+        Category::Production
+    }
+}
+
+// Lines of code in every category, they add up to `code`
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Categories {
+    pub production: usize,
+    pub test: usize,
+    pub example: usize,
+    pub bench: usize,
+}
+
+impl Categories {
+    pub fn add(&mut self, category: Category) {
+        let count = match category {
+            Category::Production => &mut self.production,
+            Category::Test => &mut self.test,
+            Category::Example => &mut self.example,
+            Category::Bench => &mut self.bench,
+        };
+        *count += 1;
+    }
+
+    pub fn rows(&self) -> [(&'static str, usize); 4] {
+        [
+            ("production", self.production),
+            ("test", self.test),
+            ("example", self.example),
+            ("bench", self.bench),
+        ]
+    }
+}
+
+impl AddAssign<&Categories> for Categories {
+    fn add_assign(&mut self, other: &Categories) {
+        self.production += other.production;
+        self.test += other.test;
+        self.example += other.example;
+        self.bench += other.bench;
+    }
+}
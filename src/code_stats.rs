@@ -1,25 +1,64 @@
-use crate::params::Params;
+use std::collections::BTreeMap;
+
+use crate::{
+    category::Categories,
+    doc_coverage::{coverage, UndocumentedItem},
+    file_stats::{sort_files, FileStats, SkippedFile},
+    items::Units,
+    json::{
+        JsonCategories, JsonCrate, JsonDir, JsonDocCoverage, JsonFile, JsonFileCoverage,
+        JsonLanguage, JsonRatio, JsonReport, JsonSkipped, JsonSummary, JsonTag, JsonUndocumented,
+        JsonUnits, JsonUnsafe, JsonUnsafeMatch, JsonWorkspace, SCHEMA_VERSION,
+    },
+    params::Params,
+    summary::Summary,
+    tags::{TagMatch, DEFAULT_TAGS},
+    tree::DirNode,
+    unsafe_code::{UnsafeMatch, UnsafeStats},
+    workspace::CrateStats,
+};
 
 #[derive(Default, Debug)]
 pub struct CodeStats {
     loc: usize,
-    todo: usize,
-    fixme: usize,
-    structs: usize,
-    fns: usize,
-    impls: usize,
-    macros: usize,
+    // Lines with each tag, tags which were not found are missing
+    tag_counts: BTreeMap<String, usize>,
+    // Tags with an owner in their metadata, per owner
+    owners: BTreeMap<String, usize>,
+    stale: usize,
+    units: Units,
+    // Also has the lines left out with `--exclude-tests`
+    categories: Categories,
+    code: usize,
+    mixed: usize,
+    mixed_docs: usize,
     comments: usize,
     docs: usize,
+    blanks: usize,
+    files: Vec<FileStats>,
+    languages: BTreeMap<&'static str, Summary>,
+    skipped: Vec<SkippedFile>,
+    tags: Vec<TagMatch>,
+    undocumented: Vec<UndocumentedItem>,
+    // Empty unless `--workspace` is used
+    crates: Vec<CrateStats>,
+    unsafe_code: UnsafeStats,
+    // Empty unless `--unsafe` is used
+    unsafe_matches: Vec<UnsafeMatch>,
 }
 
-macro_rules! getter_setter {
-    ($field_name:ident, $setter:ident) => {
+macro_rules! unit_getter {
+    ($field_name:ident) => {
         pub fn $field_name(&self) -> usize {
-            self.$field_name
+            self.units.$field_name
         }
-        pub fn $setter(&mut self) {
-            self.$field_name += 1;
+    };
+}
+
+macro_rules! getter {
+    ($field_name:ident) => {
+        pub fn $field_name(&self) -> usize {
+            self.$field_name
         }
     };
 }
@@ -29,69 +68,186 @@ impl CodeStats {
         Self::default()
     }
 
-    pub fn print(&self, params: &Params) {
+    // Percentages of code, comments, docs and blank lines in `loc`.
+    // Kinds of lines left out of `loc` by the flags are 0.
+    fn ratios(&self, params: &Params) -> (f64, f64, f64, f64) {
         let loc = self.loc();
-        let todo = self.todo();
-        let fixme = self.fixme();
+        let included = |included: bool, lines: usize| if included { lines } else { 0 };
+        let comments = included(params.comments, self.comments());
+        let docs = included(params.docs, self.docs());
+        let blanks = included(params.blanks, self.blanks());
 
-        let structs = self.structs();
-        let functions = self.fns();
-        let impl_blocks = self.impls();
-        let macros = self.macros();
+        if loc == 0 {
+            return (0.0, 0.0, 0.0, 0.0);
+        }
 
-        let comments = self.comments();
-        let docs = self.docs();
+        let unit = loc as f64 / 100.0;
+        (
+            (loc - comments - docs - blanks) as f64 / unit,
+            comments as f64 / unit,
+            docs as f64 / unit,
+            blanks as f64 / unit,
+        )
+    }
 
-        let unit = (comments + docs + (loc - comments - docs)) as f64 / 100.0;
-        let comments_ratio = comments as f64 / unit;
-        let docs_ratio = docs as f64 / unit;
-        let loc_ratio = (loc - comments - docs) as f64 / unit;
+    // Physical lines of every counted file, no matter which of them are in `loc`
+    pub fn lines(&self) -> usize {
+        self.languages_total().total()
+    }
 
+    pub fn json(&self, params: &Params) -> JsonReport {
+        let (loc_ratio, comments_ratio, docs_ratio, blanks_ratio) = self.ratios(params);
+
+        let files = if params.files {
+            self.sorted_files(params)
+                .iter()
+                .map(|file| JsonFile::new(file, &params.path))
+                .collect()
+        } else {
+            vec![]
+        };
+        let tree = params.tree.then(|| {
+            let tree = DirNode::build(&params.path, &self.files);
+            JsonDir::new(&tree, 0, params.depth)
+        });
+
+        JsonReport {
+            schema_version: SCHEMA_VERSION,
+            loc: self.loc(),
+            blanks: self.blanks(),
+            lines: self.lines(),
+            todo: self.todo(),
+            fixme: self.fixme(),
+            tag_counts: params
+                .tags
+                .iter()
+                .map(|tag| (tag.clone(), self.tag_count(tag)))
+                .collect(),
+            owners: self.owners.clone(),
+            stale: self.stale(),
+            units: JsonUnits::from(&self.units),
+            categories: JsonCategories::from(&self.categories),
+            ratio: JsonRatio {
+                loc: loc_ratio,
+                comments: comments_ratio,
+                docs: docs_ratio,
+                blanks: blanks_ratio,
+            },
+            total: JsonSummary::from(&self.languages_total()),
+            languages: self
+                .languages
+                .iter()
+                .map(|(language, summary)| JsonLanguage {
+                    language,
+                    summary: JsonSummary::from(summary),
+                })
+                .collect(),
+            files,
+            tree,
+            skipped: self
+                .skipped
+                .iter()
+                .map(|skipped| JsonSkipped::new(skipped, &params.path))
+                .collect(),
+            tags: self
+                .tags
+                .iter()
+                .map(|tag| JsonTag::new(tag, &params.path))
+                .collect(),
+            doc_coverage: params.doc_coverage.then(|| JsonDocCoverage {
+                public_items: self.public_items(),
+                documented_items: self.documented_items(),
+                coverage: self.doc_coverage(),
+                files: self
+                    .files
+                    .iter()
+                    .filter(|file| file.public_items > 0)
+                    .map(|file| JsonFileCoverage::new(file, &params.path))
+                    .collect(),
+                undocumented: self
+                    .undocumented
+                    .iter()
+                    .map(|item| JsonUndocumented::new(item, &params.path))
+                    .collect(),
+            }),
+            workspace: params.workspace.then(|| JsonWorkspace {
+                crates: self
+                    .crates
+                    .iter()
+                    .map(|stats| JsonCrate::new(stats, &params.path))
+                    .collect(),
+                total: JsonSummary::from(&self.crates_total()),
+            }),
+            unsafe_code: JsonUnsafe {
+                blocks: self.unsafe_code.blocks,
+                functions: self.unsafe_code.fns,
+                impls: self.unsafe_code.impls,
+                traits: self.unsafe_code.traits,
+                lines: self.unsafe_code.lines,
+                locations: self
+                    .unsafe_matches
+                    .iter()
+                    .map(|found| JsonUnsafeMatch::new(found, &params.path))
+                    .collect(),
+            },
+        }
+    }
+
+    pub fn print(&self, params: &Params) {
         if params.json {
-            let mut res = String::from("{");
-            res.push_str(&format!(r#""loc": {}"#, loc));
+            let json =
+                serde_json::to_string(&self.json(params)).expect("Report is always valid JSON");
+            println!("{}", json);
+        } else {
+            let (loc_ratio, comments_ratio, docs_ratio, blanks_ratio) = self.ratios(params);
+
+            println!("{}", self.loc());
+
+            if params.blanks {
+                println!("blanks: {}", self.blanks());
+                println!("lines: {}", self.lines());
+            }
 
             if params.todo {
-                res.push_str(&format!(r#","todo": {}"#, todo));
+                println!("todo: {}", self.todo());
             }
             if params.fixme {
-                res.push_str(&format!(r#","fixme": {}"#, fixme));
+                println!("fixme: {}", self.fixme());
+            }
+            for tag in &params.tags {
+                if !DEFAULT_TAGS.contains(&tag.as_str()) {
+                    println!("{}: {}", tag.to_lowercase(), self.tag_count(tag));
+                }
+            }
+            if params.stale.is_some() {
+                println!("stale: {}", self.stale());
             }
 
             if params.units {
-                res.push_str(&format!(r#","structs": {}"#, structs));
-                res.push_str(&format!(r#","functions": {}"#, functions));
-                res.push_str(&format!(r#","impl_blocks": {}"#, impl_blocks));
-                res.push_str(&format!(r#","macros": {}"#, macros));
+                for (label, count) in self.units.rows() {
+                    println!("{}: {}", label, count);
+                }
             }
 
-            if params.ratio {
-                if params.comments {
-                    res.push_str(&format!(r#","comments_ratio": "{:.1}%""#, comments_ratio));
+            if params.unsafe_code {
+                for (label, count) in self.unsafe_code.rows() {
+                    println!("{}: {}", label, count);
                 }
-                if params.docs {
-                    res.push_str(&format!(r#","docs_ratio": "{:.1}%""#, docs_ratio));
-                }
-                res.push_str(&format!(r#","loc_ratio": "{:.1}%""#, loc_ratio));
             }
 
-            res.push('}');
-            println!("{}", res);
-        } else {
-            println!("{}", loc);
-
-            if params.todo {
-                println!("todo: {}", todo);
-            }
-            if params.fixme {
-                println!("fixme: {}", fixme);
+            if params.categories {
+                for (label, count) in self.categories.rows() {
+                    println!("{}: {}", label, count);
+                }
             }
 
-            if params.units {
-                println!("structs: {}", structs);
-                println!("functions: {}", functions);
-                println!("impl blocks: {}", impl_blocks);
-                println!("macros: {}", macros);
+            if params.doc_coverage {
+                println!(
+                    "doc coverage: {:.1}% ({}/{})",
+                    self.doc_coverage(),
+                    self.documented_items(),
+                    self.public_items()
+                );
             }
 
             if params.ratio {
@@ -101,8 +257,64 @@ impl CodeStats {
                 if params.docs {
                     println!("docs: {:.1}%", docs_ratio);
                 }
+                if params.blanks {
+                    println!("blanks: {:.1}%", blanks_ratio);
+                }
                 println!("loc: {:.1}%", loc_ratio);
             }
+
+            if params.languages {
+                println!();
+                self.print_languages();
+            }
+
+            if params.workspace {
+                println!();
+                self.print_crates();
+            }
+
+            if params.tree {
+                println!();
+                DirNode::build(&params.path, &self.files).print(params.depth);
+            }
+
+            if params.files {
+                println!();
+                self.print_files(params);
+            }
+
+            if params.owners {
+                println!();
+                self.print_owners();
+            }
+
+            // Same format as compiler messages, so editors can jump to them
+            if params.list_tags {
+                println!();
+                for tag in &self.tags {
+                    println!("{}", tag);
+                }
+            }
+
+            if params.doc_coverage {
+                println!();
+                self.print_doc_coverage(params);
+            }
+
+            if params.unsafe_code && !self.unsafe_matches.is_empty() {
+                println!();
+                for found in &self.unsafe_matches {
+                    println!("{}", found);
+                }
+            }
+        }
+
+        // Keeps stdout clean for scripts reading the numbers
+        if !self.skipped.is_empty() {
+            eprintln!("Skipped files ({}):", self.skipped.len());
+            for skipped in &self.skipped {
+                eprintln!("  {}: {}", skipped.path.display(), skipped.reason);
+            }
         }
     }
 
@@ -114,12 +326,371 @@ impl CodeStats {
         self.loc
     }
 
-    getter_setter!(todo, add_todo);
-    getter_setter!(fixme, add_fixme);
-    getter_setter!(structs, add_structs);
-    getter_setter!(fns, add_fns);
-    getter_setter!(impls, add_impls);
-    getter_setter!(macros, add_macros);
-    getter_setter!(comments, add_comments);
-    getter_setter!(docs, add_docs);
+    pub fn add_file(&mut self, file: FileStats) {
+        self.code += file.code;
+        self.mixed += file.mixed;
+        self.mixed_docs += file.mixed_docs;
+        self.comments += file.comments;
+        self.docs += file.docs;
+        self.blanks += file.blanks;
+        self.units += &file.units;
+        self.unsafe_code += &file.unsafe_code;
+        self.languages
+            .entry(file.language)
+            .or_default()
+            .add_file(&file);
+        self.files.push(file);
+    }
+
+    pub fn merge(&mut self, other: CodeStats) {
+        self.loc += other.loc;
+        for (tag, count) in other.tag_counts {
+            *self.tag_counts.entry(tag).or_default() += count;
+        }
+        for (owner, count) in other.owners {
+            *self.owners.entry(owner).or_default() += count;
+        }
+        self.stale += other.stale;
+        self.categories += &other.categories;
+        for file in other.files {
+            self.add_file(file);
+        }
+        self.skipped.extend(other.skipped);
+        self.tags.extend(other.tags);
+        self.undocumented.extend(other.undocumented);
+        self.unsafe_matches.extend(other.unsafe_matches);
+    }
+
+    pub fn add_tag(&mut self, tag: TagMatch) {
+        self.tags.push(tag);
+    }
+
+    // Empty unless `--list-tags` is used
+    pub fn tags(&self) -> &[TagMatch] {
+        &self.tags
+    }
+
+    pub fn add_unsafe(&mut self, found: UnsafeMatch) {
+        self.unsafe_matches.push(found);
+    }
+
+    pub fn unsafe_code(&self) -> &UnsafeStats {
+        &self.unsafe_code
+    }
+
+    // Empty unless `--unsafe` is used
+    pub fn unsafe_matches(&self) -> &[UnsafeMatch] {
+        &self.unsafe_matches
+    }
+
+    pub fn set_crates(&mut self, crates: Vec<CrateStats>) {
+        self.crates = crates;
+    }
+
+    pub fn crates(&self) -> &[CrateStats] {
+        &self.crates
+    }
+
+    // Every member crate together
+    pub fn crates_total(&self) -> Summary {
+        let mut total = Summary::default();
+        for stats in &self.crates {
+            total.merge(&stats.summary);
+        }
+        total
+    }
+
+    pub fn add_categories(&mut self, categories: &Categories) {
+        self.categories += categories;
+    }
+
+    pub fn categories(&self) -> &Categories {
+        &self.categories
+    }
+
+    pub fn add_undocumented(&mut self, item: UndocumentedItem) {
+        self.undocumented.push(item);
+    }
+
+    // Empty unless `--doc-coverage` is used
+    pub fn undocumented(&self) -> &[UndocumentedItem] {
+        &self.undocumented
+    }
+
+    pub fn public_items(&self) -> usize {
+        self.files.iter().map(|file| file.public_items).sum()
+    }
+
+    pub fn documented_items(&self) -> usize {
+        self.files.iter().map(|file| file.documented_items).sum()
+    }
+
+    // Percentage of public items with docs
+    pub fn doc_coverage(&self) -> f64 {
+        coverage(self.documented_items(), self.public_items())
+    }
+
+    pub fn add_skipped(&mut self, skipped: SkippedFile) {
+        self.skipped.push(skipped);
+    }
+
+    pub fn skipped(&self) -> &[SkippedFile] {
+        &self.skipped
+    }
+
+    // Walking order depends on the file system and the number of
+    // threads, sorting keeps the output stable
+    pub fn sort_files_by_path(&mut self) {
+        self.files.sort_by(|a, b| a.path.cmp(&b.path));
+        self.skipped.sort_by(|a, b| a.path.cmp(&b.path));
+        // Stable sort keeps tags on the same line in order
+        self.tags.sort_by(|a, b| a.location.cmp(&b.location));
+        self.undocumented
+            .sort_by(|a, b| a.location.cmp(&b.location));
+        self.unsafe_matches
+            .sort_by(|a, b| a.location.cmp(&b.location));
+    }
+
+    pub fn files(&self) -> &[FileStats] {
+        &self.files
+    }
+
+    pub fn languages(&self) -> &BTreeMap<&'static str, Summary> {
+        &self.languages
+    }
+
+    pub fn languages_total(&self) -> Summary {
+        let mut total = Summary::default();
+        for summary in self.languages.values() {
+            total.merge(summary);
+        }
+        total
+    }
+
+    fn print_languages(&self) {
+        let rows = self
+            .languages
+            .iter()
+            .map(|(language, summary)| (*language, summary))
+            .collect::<Vec<(&str, &Summary)>>();
+        print_summary_table("language", &rows, &self.languages_total());
+    }
+
+    fn print_crates(&self) {
+        let rows = self
+            .crates
+            .iter()
+            .map(|stats| (stats.name.as_str(), &stats.summary))
+            .collect::<Vec<(&str, &Summary)>>();
+        print_summary_table("crate", &rows, &self.crates_total());
+    }
+
+    fn print_owners(&self) {
+        let width = self
+            .owners
+            .keys()
+            .map(|owner| owner.len())
+            .max()
+            .unwrap_or(0)
+            .max(5);
+
+        println!("{:<width$}  {:>8}", "owner", "tags");
+        for (owner, count) in &self.owners {
+            println!("{:<width$}  {:>8}", owner, count);
+        }
+    }
+
+    fn print_doc_coverage(&self, params: &Params) {
+        let files = self
+            .files
+            .iter()
+            .filter(|file| file.public_items > 0)
+            .collect::<Vec<&FileStats>>();
+        let paths = files
+            .iter()
+            .map(|file| file.display_path(&params.path))
+            .collect::<Vec<String>>();
+        let width = paths
+            .iter()
+            .map(|path| path.len())
+            .max()
+            .unwrap_or(0)
+            .max(4);
+
+        println!(
+            "{:<width$}  {:>10}  {:>10}  {:>8}",
+            "path", "public", "documented", "coverage"
+        );
+        for (file, path) in files.iter().zip(paths) {
+            println!(
+                "{:<width$}  {:>10}  {:>10}  {:>7.1}%",
+                path,
+                file.public_items,
+                file.documented_items,
+                coverage(file.documented_items, file.public_items)
+            );
+        }
+
+        if !self.undocumented.is_empty() {
+            println!();
+            for item in &self.undocumented {
+                println!("{}", item);
+            }
+        }
+    }
+
+    fn sorted_files(&self, params: &Params) -> Vec<FileStats> {
+        let mut files = self.files.clone();
+        sort_files(&mut files, params.sort);
+        files
+    }
+
+    fn print_files(&self, params: &Params) {
+        let files = self.sorted_files(params);
+        let paths = files
+            .iter()
+            .map(|file| file.display_path(&params.path))
+            .collect::<Vec<String>>();
+
+        let path_width = paths
+            .iter()
+            .map(|path| path.len())
+            .max()
+            .unwrap_or(0)
+            .max(4);
+        let lang_width = files
+            .iter()
+            .map(|file| file.language.len())
+            .max()
+            .unwrap_or(0)
+            .max(8);
+
+        let mut header = format!(
+            "{:<path_width$}  {:<lang_width$}  {:>8}  {:>8}  {:>8}  {:>8}  {:>8}",
+            "path", "language", "code", "mixed", "comments", "docs", "blanks"
+        );
+        // Every unit column is as wide as its label
+        if params.units {
+            for (label, _) in Units::default().rows() {
+                header.push_str(&format!("  {:>8}", label));
+            }
+        }
+        println!("{}", header);
+
+        for (file, path) in files.iter().zip(paths) {
+            let mut row = format!(
+                "{:<path_width$}  {:<lang_width$}  {:>8}  {:>8}  {:>8}  {:>8}  {:>8}",
+                path, file.language, file.code, file.mixed, file.comments, file.docs, file.blanks
+            );
+            if params.units {
+                for (label, count) in file.units.rows() {
+                    row.push_str(&format!("  {:>width$}", count, width = label.len().max(8)));
+                }
+            }
+            println!("{}", row);
+        }
+    }
+
+    pub fn add_tag_count(&mut self, tag: &str) {
+        *self.tag_counts.entry(tag.to_string()).or_default() += 1;
+    }
+
+    // Number of lines with the tag in a comment
+    pub fn tag_count(&self, tag: &str) -> usize {
+        self.tag_counts.get(tag).copied().unwrap_or(0)
+    }
+
+    pub fn tag_counts(&self) -> &BTreeMap<String, usize> {
+        &self.tag_counts
+    }
+
+    pub fn add_owner(&mut self, owner: &str) {
+        *self.owners.entry(owner.to_string()).or_default() += 1;
+    }
+
+    pub fn owners(&self) -> &BTreeMap<String, usize> {
+        &self.owners
+    }
+
+    pub fn add_stale(&mut self) {
+        self.stale += 1;
+    }
+
+    // Always 0 unless `--stale` is used
+    pub fn stale(&self) -> usize {
+        self.stale
+    }
+
+    pub fn todo(&self) -> usize {
+        self.tag_count("TODO")
+    }
+
+    pub fn fixme(&self) -> usize {
+        self.tag_count("FIXME")
+    }
+
+    pub fn units(&self) -> &Units {
+        &self.units
+    }
+
+    unit_getter!(structs);
+    unit_getter!(enums);
+    unit_getter!(unions);
+    unit_getter!(traits);
+    unit_getter!(type_aliases);
+    unit_getter!(fns);
+    unit_getter!(free_fns);
+    unit_getter!(methods);
+    unit_getter!(trait_impl_methods);
+    unit_getter!(required_methods);
+    unit_getter!(provided_methods);
+    unit_getter!(test_fns);
+    unit_getter!(closures);
+    unit_getter!(impls);
+    unit_getter!(mods);
+    unit_getter!(consts);
+    unit_getter!(statics);
+    unit_getter!(extern_blocks);
+    unit_getter!(macros);
+    getter!(code);
+    getter!(mixed);
+    getter!(mixed_docs);
+    getter!(comments);
+    getter!(docs);
+    getter!(blanks);
+}
+
+// Files and lines per row with the total below, `title` heads the names
+fn print_summary_table(title: &str, rows: &[(&str, &Summary)], total: &Summary) {
+    let width = rows
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0)
+        .max(8);
+    let line = "-".repeat(width + 6 * 10);
+
+    let row = |name: &str, summary: &Summary| {
+        println!(
+            "{:<width$}  {:>8}  {:>8}  {:>8}  {:>8}  {:>8}  {:>8}",
+            name,
+            summary.files,
+            summary.code,
+            summary.comments,
+            summary.docs,
+            summary.blanks,
+            summary.total()
+        );
+    };
+
+    println!(
+        "{:<width$}  {:>8}  {:>8}  {:>8}  {:>8}  {:>8}  {:>8}",
+        title, "files", "code", "comments", "docs", "blanks", "total"
+    );
+    println!("{}", line);
+    for (name, summary) in rows {
+        row(name, summary);
+    }
+    println!("{}", line);
+    row("Total", total);
 }
@@ -0,0 +1,38 @@
+use std::{fs, path::Path};
+
+use serde::Deserialize;
+
+pub const CONFIG_FILE: &str = ".plc.toml";
+
+// Settings read from `.plc.toml` in the directory plc is pointed at.
+// They add to the command line options instead of replacing them.
+#[derive(Deserialize, Default, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub tags: Vec<String>,
+}
+
+impl Config {
+    pub fn from_dir(dir: &Path) -> Self {
+        let path = dir.join(CONFIG_FILE);
+        if !path.is_file() {
+            return Self::default();
+        }
+
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                log::warn!("Failed to read {:?}: {}", path, e);
+                return Self::default();
+            }
+        };
+
+        match toml::from_str(&content) {
+            Ok(config) => config,
+            Err(e) => {
+                log::warn!("Invalid config {:?}: {}", path, e);
+                Self::default()
+            }
+        }
+    }
+}
@@ -1,136 +1,288 @@
-use std::{fs, path::Path};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Cursor, Read},
+    path::Path,
+};
 
-use crate::{code_stats::CodeStats, params::Params};
+use crate::{
+    category::Category,
+    code_stats::CodeStats,
+    doc_coverage::{DocTracker, UndocumentedItem},
+    file_stats::{FileStats, SkipReason, SkippedFile},
+    items::{ItemScanner, LineItems, Units},
+    languages::Language,
+    lexer::{Lexer, LineScan},
+    location::Location,
+    params::Params,
+    tags::{find_tags, is_stale, FoundTag, TagMatch},
+    unsafe_code::UnsafeMatch,
+};
 
-pub fn count_lines(path: &Path, params: &Params, stats: &mut CodeStats) {
-    let file_str = fs::read_to_string(path).unwrap();
+enum LineKind {
+    Code,
+    // Code followed or preceded by a comment on the same line
+    Mixed,
+    Comment,
+    Doc,
+    Blank,
+}
 
-    let mut lines = file_str.lines().collect::<Vec<&str>>();
+// Tags are only looked for in comments, so a `TODO` in an
+// identifier or a string is not counted
+fn line_tags<'t, 'l>(
+    line: &'l str,
+    scan: &LineScan,
+    language: &Language,
+    tags: &'t [String],
+) -> Vec<FoundTag<'t, 'l>> {
+    scan.comments
+        .iter()
+        .flat_map(|range| find_tags(&line[range.clone()], language, tags))
+        .collect()
+}
 
-    let mut i = 0;
-    let mut in_multi_comment = false;
-    while i < lines.len() {
-        let line = lines[i].trim();
-        let mut chars = line.chars();
-        let first_char = chars.next();
-        let second_char = chars.next();
-        let third_char = chars.next();
+// Counts per tag are per line, a line with two TODOs adds one.
+// Owners and stale tags are counted for every occurrence.
+fn count_tags(
+    found: Vec<FoundTag>,
+    path: &Path,
+    number: usize,
+    params: &Params,
+    stats: &mut CodeStats,
+) {
+    for tag in &params.tags {
+        if found.iter().any(|found| found.tag == tag) {
+            stats.add_tag_count(tag);
+        }
+    }
 
-        if line.is_empty() {
-            lines.remove(i);
-            continue;
+    for found in found {
+        if let Some(owner) = &found.meta.owner {
+            stats.add_owner(owner);
         }
 
-        if in_multi_comment {
-            let mut reverse_chars = line.chars().rev();
-            if reverse_chars.next() == Some('/') && reverse_chars.next() == Some('*') {
-                in_multi_comment = false;
-            }
+        let stale = params
+            .stale
+            .is_some_and(|max_age| is_stale(&found.meta, max_age));
+        if stale {
+            stats.add_stale();
+        }
 
-            if line.contains("TODO") {
-                stats.add_todo();
-            }
-            if line.contains("FIXME") {
-                stats.add_fixme();
-            }
+        if params.list_tags {
+            stats.add_tag(TagMatch {
+                location: Location::new(path, number),
+                tag: found.tag.to_string(),
+                meta: found.meta,
+                text: found.text.to_string(),
+                stale,
+            });
+        }
+    }
+}
 
-            if !params.comments {
-                lines.remove(i);
-                continue;
-            } else {
-                if params.ratio {
-                    stats.add_comments();
-                }
-            }
+fn count_unsafe(
+    found: &LineItems,
+    path: &Path,
+    number: usize,
+    params: &Params,
+    file_stats: &mut FileStats,
+    stats: &mut CodeStats,
+) {
+    if found.in_unsafe {
+        file_stats.unsafe_code.lines += 1;
+    }
+
+    for kind in &found.unsafe_kinds {
+        file_stats.unsafe_code.add(*kind);
+        if params.unsafe_code {
+            stats.add_unsafe(UnsafeMatch {
+                location: Location::new(path, number),
+                kind: *kind,
+            });
         }
+    }
+}
 
-        if first_char == Some('/') && second_char == Some('*') {
-            if line.contains("TODO") {
-                stats.add_todo();
-            }
-            if line.contains("FIXME") {
-                stats.add_fixme();
-            }
+// Returns a reader yielding UTF-8 text. UTF-16 with a BOM is decoded,
+// files with a NUL byte at the start are taken for binary ones and
+// anything else is read as UTF-8, replacing invalid sequences.
+fn open(path: &Path) -> Result<Box<dyn BufRead>, SkipReason> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let head = reader.fill_buf()?;
 
-            in_multi_comment = true;
+    if head.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        reader.consume(3);
+        return Ok(Box::new(reader));
+    }
+
+    if head.starts_with(&[0xFF, 0xFE]) || head.starts_with(&[0xFE, 0xFF]) {
+        let little_endian = head[0] == 0xFF;
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes)?;
 
-            if !params.comments {
-                lines.remove(i);
-                continue;
-            } else {
-                if params.ratio {
-                    stats.add_comments();
+        let units = bytes[2..]
+            .chunks_exact(2)
+            .map(|pair| {
+                if little_endian {
+                    u16::from_le_bytes([pair[0], pair[1]])
+                } else {
+                    u16::from_be_bytes([pair[0], pair[1]])
                 }
-            }
+            })
+            .collect::<Vec<u16>>();
+        let text = String::from_utf16_lossy(&units);
+        return Ok(Box::new(Cursor::new(text.into_bytes())));
+    }
+
+    if head.contains(&0) {
+        return Err(SkipReason::Binary);
+    }
+
+    Ok(Box::new(reader))
+}
+
+// Counts into `stats` of its own, so nothing of a file which fails
+// halfway through ends up in the totals
+pub fn count_lines(path: &Path, params: &Params, stats: &mut CodeStats) {
+    let mut file_totals = CodeStats::new();
+
+    match count_file(path, params, &mut file_totals) {
+        Ok(()) => stats.merge(file_totals),
+        Err(reason) => {
+            log::warn!("Skipped {:?}: {}", path, reason);
+            stats.add_skipped(SkippedFile {
+                path: path.to_path_buf(),
+                reason,
+            });
         }
+    }
+}
 
-        if first_char == Some('/')
-            && second_char == Some('/')
-            && (third_char != Some('/') && third_char != Some('!'))
-        {
-            if line.contains("TODO") {
-                stats.add_todo();
-            }
-            if line.contains("FIXME") {
-                stats.add_fixme();
-            }
+fn count_file(path: &Path, params: &Params, stats: &mut CodeStats) -> Result<(), SkipReason> {
+    let language = Language::from_path(path);
+    log::debug!("Language of {:?}: {}", path.file_name(), language.name);
 
-            if !params.comments {
-                lines.remove(i);
-                continue;
-            } else {
-                if params.ratio {
-                    stats.add_comments();
-                }
-            }
+    let mut reader = open(path)?;
+    let mut buf = vec![];
+
+    let mut file_stats = FileStats::new(path, language.name);
+    let file_category = Category::from_path(path, &params.path);
+
+    let mut lexer = Lexer::new(language);
+    // Items are only recognized in Rust files, where blocks are always
+    // followed to find the test code
+    let scan_items = language.is_rust();
+    let mut items = ItemScanner::new();
+    let mut units = Units::default();
+    let mut docs = DocTracker::new();
+    let doc_coverage = params.doc_coverage && language.is_rust();
+    let mut number = 0;
+    loop {
+        number += 1;
+        buf.clear();
+        if reader.read_until(b'\n', &mut buf)? == 0 {
+            break;
         }
+        let line = String::from_utf8_lossy(&buf);
+        let line = line.trim();
 
-        if first_char == Some('/')
-            && second_char == Some('/')
-            && (third_char == Some('/') || third_char == Some('!'))
-        {
-            // println!("{:#?}", stats);
-            if lines[i].contains("TODO") {
-                stats.add_todo();
-            }
-            if lines[i].contains("FIXME") {
-                stats.add_fixme();
+        let scan = if line.is_empty() {
+            LineScan::default()
+        } else {
+            lexer.scan_line(line)
+        };
+        let kind = if line.is_empty() {
+            LineKind::Blank
+        } else if scan.code && scan.has_comment() {
+            LineKind::Mixed
+        } else if scan.code {
+            LineKind::Code
+        } else if scan.doc {
+            LineKind::Doc
+        } else {
+            LineKind::Comment
+        };
+        let code = matches!(kind, LineKind::Code | LineKind::Mixed);
+        // Kept apart until the category of the line is known
+        let mut line_units = Units::default();
+        let found = if code && scan_items {
+            items.scan_line(line, &scan, &mut line_units)
+        } else {
+            LineItems {
+                test: items.in_test(),
+                ..Default::default()
             }
+        };
+        let category = match file_category {
+            Category::Production if found.test => Category::Test,
+            category => category,
+        };
+        if code {
+            file_stats.categories.add(category);
+        }
+        // Left in the categories, but out of every other count
+        if params.exclude_tests && category != Category::Production {
+            continue;
+        }
 
-            if !params.docs {
-                lines.remove(i);
-                continue;
-            } else {
-                if params.ratio {
-                    stats.add_docs();
-                }
-            }
+        units += &line_units;
+        if language.is_rust() {
+            count_unsafe(&found, path, number, params, &mut file_stats, stats);
         }
+        let tags = line_tags(line, &scan, language, &params.tags);
+        count_tags(tags, path, number, params, stats);
 
-        if params.units {
-            if line.starts_with("struct ") || line.starts_with("pub struct ") {
-                stats.add_structs();
-            }
-            if line.starts_with("fn ")
-                || line.starts_with("async fn ")
-                || line.starts_with("pub fn ")
-                || line.starts_with("pub async fn ")
-            {
-                stats.add_fns();
-            }
-            if line.starts_with("impl ") {
-                stats.add_impls();
+        match kind {
+            LineKind::Blank => file_stats.blanks += 1,
+            LineKind::Comment => file_stats.comments += 1,
+            LineKind::Doc => {
+                file_stats.docs += 1;
+                if doc_coverage {
+                    docs.doc_line(line);
+                }
             }
-            if line.starts_with("macro_rules!") {
-                stats.add_macros();
+            LineKind::Code | LineKind::Mixed => {
+                // Mixed lines still hold code, so they are counted as code too
+                file_stats.code += 1;
+                if let LineKind::Mixed = kind {
+                    file_stats.mixed += 1;
+                    if scan.doc {
+                        file_stats.mixed_docs += 1;
+                    }
+                }
+
+                // Code in a string from an earlier line is no item
+                if doc_coverage && !scan.starts_in_string {
+                    if let Some((kind, name, documented)) = docs.code_line(line) {
+                        file_stats.public_items += 1;
+                        if documented {
+                            file_stats.documented_items += 1;
+                        } else {
+                            stats.add_undocumented(UndocumentedItem {
+                                location: Location::new(path, number),
+                                kind,
+                                name: name.to_string(),
+                            });
+                        }
+                    }
+                }
             }
         }
+    }
+
+    if params.units && scan_items {
+        file_stats.units = units;
+    }
 
-        i += 1;
+    stats.add_categories(&file_stats.categories);
+    if params.exclude_tests && file_category != Category::Production {
+        return Ok(());
     }
 
-    // println!("{}", lines.join("\n"));
+    let loc = file_stats.loc(params);
+    log::info!("Lines in {:?}: {}", path.file_name(), loc);
+    stats.add_loc(loc);
+    stats.add_file(file_stats);
 
-    log::info!("Lines in {:?}: {}", params.path.file_name(), lines.len());
-    stats.add_loc(lines.len());
+    Ok(())
 }
@@ -0,0 +1,218 @@
+use std::{
+    collections::BTreeMap,
+    io::{self, ErrorKind},
+    path::Path,
+};
+
+use crate::{
+    code_stats::CodeStats, config::Config, file_stats::FileStats, json::JsonReport,
+    module_tree::count_module_tree, params::Params, summary::Summary, tree::DirNode,
+    walk::visit_dir_parallel, workspace::Workspace,
+};
+
+/// Builder for a single run of the line counter.
+///
+/// ```no_run
+/// let report = plc::Counter::new("./src")
+///     .extensions([".rs"])
+///     .comments(true)
+///     .run()
+///     .unwrap();
+///
+/// println!("{}", report.stats().loc());
+/// ```
+///
+/// Code, comments, docs and blank lines are always counted separately,
+/// the flags only decide which of them make up `loc`.
+pub struct Counter {
+    params: Params,
+}
+
+impl Counter {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            params: Params::new(path.as_ref().to_path_buf()),
+        }
+    }
+
+    pub fn from_params(params: Params) -> Self {
+        Self { params }
+    }
+
+    /// Extensions are matched against the end of the file name, e.g. `".rs"`.
+    pub fn extensions<I, S>(mut self, extensions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.params.extensions = extensions.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn hidden(mut self, hidden: bool) -> Self {
+        self.params.hidden = hidden;
+        self
+    }
+
+    /// Count comments into `loc`.
+    pub fn comments(mut self, comments: bool) -> Self {
+        self.params.comments = comments;
+        self
+    }
+
+    /// Count documentation into `loc`.
+    pub fn docs(mut self, docs: bool) -> Self {
+        self.params.docs = docs;
+        self
+    }
+
+    /// Count blank lines into `loc`.
+    pub fn blanks(mut self, blanks: bool) -> Self {
+        self.params.blanks = blanks;
+        self
+    }
+
+    pub fn ratio(mut self, ratio: bool) -> Self {
+        self.params.ratio = ratio;
+        self
+    }
+
+    /// Count Rust units in `.rs` files, other files have none. Ignored
+    /// unless `.rs` is one of the extensions.
+    pub fn units(mut self, units: bool) -> Self {
+        self.params.units = units;
+        self
+    }
+
+    /// Report how many `pub` items have doc comments and list the ones
+    /// without. Ignored unless `.rs` is one of the extensions.
+    pub fn doc_coverage(mut self, doc_coverage: bool) -> Self {
+        self.params.doc_coverage = doc_coverage;
+        self
+    }
+
+    /// Leave tests, examples and benches out of `loc` and every other
+    /// count. They are still counted in [`CodeStats::categories`].
+    pub fn exclude_tests(mut self, exclude_tests: bool) -> Self {
+        self.params.exclude_tests = exclude_tests;
+        self
+    }
+
+    /// Roll the counts up per member crate of the Cargo workspace
+    /// (or the single package) the path points at.
+    pub fn workspace(mut self, workspace: bool) -> Self {
+        self.params.workspace = workspace;
+        self
+    }
+
+    /// Count only the files compiled into the crates at the path, found by
+    /// following `mod` declarations from the roots of their targets.
+    pub fn module_tree(mut self, module_tree: bool) -> Self {
+        self.params.module_tree = module_tree;
+        self
+    }
+
+    /// List every unsafe block, function, impl and trait. They are
+    /// counted in [`CodeStats::unsafe_code`] either way.
+    pub fn unsafe_code(mut self, unsafe_code: bool) -> Self {
+        self.params.unsafe_code = unsafe_code;
+        self
+    }
+
+    /// Number of threads walking the directories, 1 walks sequentially.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.params.threads = threads.max(1);
+        self
+    }
+
+    /// Comment tags to count besides TODO and FIXME, e.g. `"HACK"`.
+    pub fn tags<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.params.add_tags(tags);
+        self
+    }
+
+    /// Flag tags dated more than `days` ago, e.g. `TODO(alice, 2024-01-31)`.
+    pub fn stale(mut self, days: u64) -> Self {
+        self.params.stale = Some(days);
+        self
+    }
+
+    pub fn params(&self) -> &Params {
+        &self.params
+    }
+
+    /// Fails only if the path does not exist. Files and directories which
+    /// cannot be read are listed in [`CodeStats::skipped`] instead.
+    pub fn run(mut self) -> io::Result<Report> {
+        if !self.params.path.exists() {
+            return Err(io::Error::new(
+                ErrorKind::NotFound,
+                format!("{} does not exist", self.params.path.display()),
+            ));
+        }
+
+        let rust = self.params.extensions.iter().any(|ext| ext == ".rs");
+        self.params.units &= rust;
+        self.params.doc_coverage &= rust;
+
+        let config = Config::from_dir(&self.params.path);
+        self.params.add_tags(config.tags);
+
+        let mut stats = if self.params.module_tree {
+            count_module_tree(&self.params)
+        } else {
+            visit_dir_parallel(&self.params, &self.params.path)
+        };
+        stats.sort_files_by_path();
+
+        if self.params.workspace {
+            let workspace = Workspace::from_dir(&self.params.path);
+            stats.set_crates(workspace.crate_stats(stats.files()));
+        }
+
+        Ok(Report {
+            params: self.params,
+            stats,
+        })
+    }
+}
+
+/// Result of [`Counter::run`].
+pub struct Report {
+    params: Params,
+    stats: CodeStats,
+}
+
+impl Report {
+    pub fn params(&self) -> &Params {
+        &self.params
+    }
+
+    pub fn stats(&self) -> &CodeStats {
+        &self.stats
+    }
+
+    pub fn files(&self) -> &[FileStats] {
+        self.stats.files()
+    }
+
+    pub fn languages(&self) -> &BTreeMap<&'static str, Summary> {
+        self.stats.languages()
+    }
+
+    pub fn tree(&self) -> DirNode {
+        DirNode::build(&self.params.path, self.stats.files())
+    }
+
+    pub fn json(&self) -> JsonReport {
+        self.stats.json(&self.params)
+    }
+
+    pub fn print(&self) {
+        self.stats.print(&self.params);
+    }
+}
@@ -0,0 +1,63 @@
+use std::fmt;
+
+use crate::{
+    items::{is_attribute, parse_public_item, ItemKind},
+    location::Location,
+};
+
+// Public item without a doc comment in front of it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UndocumentedItem {
+    pub location: Location,
+    pub kind: ItemKind,
+    pub name: String,
+}
+
+// `path:line: fn name`
+impl fmt::Display for UndocumentedItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} {}",
+            self.location,
+            self.kind.keyword(),
+            self.name
+        )
+    }
+}
+
+// Percentage of public items with docs, 100 when there are none
+pub fn coverage(documented: usize, public: usize) -> f64 {
+    if public == 0 {
+        return 100.0;
+    }
+    documented as f64 * 100.0 / public as f64
+}
+
+// Doc comments of the lines before an item, attributes and blank lines
+// may come in between. Inner docs (`//!`) belong to the enclosing module.
+#[derive(Debug, Default)]
+pub struct DocTracker {
+    documented: bool,
+}
+
+impl DocTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn doc_line(&mut self, line: &str) {
+        self.documented = !line.starts_with("//!") && !line.starts_with("/*!");
+    }
+
+    // Returns the public item the line starts and whether it has docs
+    pub fn code_line<'l>(&mut self, line: &'l str) -> Option<(ItemKind, &'l str, bool)> {
+        if is_attribute(line) {
+            self.documented |= line.starts_with("#[doc");
+            return None;
+        }
+
+        let documented = std::mem::take(&mut self.documented);
+        parse_public_item(line).map(|(kind, name)| (kind, name, documented))
+    }
+}
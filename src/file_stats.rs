@@ -0,0 +1,165 @@
+use std::{
+    fmt, io,
+    path::{Path, PathBuf},
+};
+
+use clap::ValueEnum;
+
+use crate::{category::Categories, items::Units, params::Params, unsafe_code::UnsafeStats};
+
+#[derive(ValueEnum, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortBy {
+    #[default]
+    Path,
+    Language,
+    Code,
+    Mixed,
+    Comments,
+    Docs,
+    Blanks,
+    Structs,
+    Enums,
+    Unions,
+    Traits,
+    TypeAliases,
+    Functions,
+    FreeFunctions,
+    Methods,
+    TraitImplMethods,
+    RequiredMethods,
+    ProvidedMethods,
+    TestFunctions,
+    Closures,
+    Impls,
+    Mods,
+    Consts,
+    Statics,
+    ExternBlocks,
+    Macros,
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct FileStats {
+    pub path: PathBuf,
+    pub language: &'static str,
+    pub code: usize,
+    // Lines of code with a comment on them, part of `code`
+    pub mixed: usize,
+    // Mixed lines whose comment is a doc comment, part of `mixed`
+    pub mixed_docs: usize,
+    pub comments: usize,
+    pub docs: usize,
+    pub blanks: usize,
+    pub units: Units,
+    pub categories: Categories,
+    // Counted in every Rust file
+    pub unsafe_code: UnsafeStats,
+    // Only counted with `--doc-coverage`
+    pub public_items: usize,
+    pub documented_items: usize,
+}
+
+impl FileStats {
+    pub fn new(path: &Path, language: &'static str) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            language,
+            ..Default::default()
+        }
+    }
+
+    // Code plus the kinds of lines the flags include in the headline number
+    pub fn loc(&self, params: &Params) -> usize {
+        let mut loc = self.code;
+        if params.comments {
+            loc += self.comments;
+        }
+        if params.docs {
+            loc += self.docs;
+        }
+        if params.blanks {
+            loc += self.blanks;
+        }
+        loc
+    }
+
+    pub fn display_path(&self, root: &Path) -> String {
+        display_path(&self.path, root)
+    }
+}
+
+// Path relative to the directory plc was pointed at
+pub fn display_path(path: &Path, root: &Path) -> String {
+    match path.strip_prefix(root) {
+        Ok(relative) if !relative.as_os_str().is_empty() => relative.display().to_string(),
+        _ => path.display().to_string(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkipReason {
+    Binary,
+    Unreadable(String),
+}
+
+impl From<io::Error> for SkipReason {
+    fn from(value: io::Error) -> Self {
+        Self::Unreadable(value.to_string())
+    }
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::Binary => write!(f, "binary file"),
+            SkipReason::Unreadable(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+// File or directory which could not be counted
+#[derive(Debug, Clone)]
+pub struct SkippedFile {
+    pub path: PathBuf,
+    pub reason: SkipReason,
+}
+
+// Numbers are sorted from the biggest, names alphabetically
+pub fn sort_files(files: &mut [FileStats], sort_by: SortBy) {
+    match sort_by {
+        SortBy::Path => files.sort_by(|a, b| a.path.cmp(&b.path)),
+        SortBy::Language => {
+            files.sort_by(|a, b| a.language.cmp(b.language).then(a.path.cmp(&b.path)))
+        }
+        _ => {
+            let key = |file: &FileStats| match sort_by {
+                SortBy::Code => file.code,
+                SortBy::Mixed => file.mixed,
+                SortBy::Comments => file.comments,
+                SortBy::Docs => file.docs,
+                SortBy::Blanks => file.blanks,
+                SortBy::Structs => file.units.structs,
+                SortBy::Enums => file.units.enums,
+                SortBy::Unions => file.units.unions,
+                SortBy::Traits => file.units.traits,
+                SortBy::TypeAliases => file.units.type_aliases,
+                SortBy::Functions => file.units.fns,
+                SortBy::FreeFunctions => file.units.free_fns,
+                SortBy::Methods => file.units.methods,
+                SortBy::TraitImplMethods => file.units.trait_impl_methods,
+                SortBy::RequiredMethods => file.units.required_methods,
+                SortBy::ProvidedMethods => file.units.provided_methods,
+                SortBy::TestFunctions => file.units.test_fns,
+                SortBy::Closures => file.units.closures,
+                SortBy::Impls => file.units.impls,
+                SortBy::Mods => file.units.mods,
+                SortBy::Consts => file.units.consts,
+                SortBy::Statics => file.units.statics,
+                SortBy::ExternBlocks => file.units.extern_blocks,
+                SortBy::Macros => file.units.macros,
+                SortBy::Path | SortBy::Language => unreachable!(),
+            };
+            files.sort_by(|a, b| key(b).cmp(&key(a)).then(a.path.cmp(&b.path)));
+        }
+    }
+}
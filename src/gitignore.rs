@@ -0,0 +1,201 @@
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+
+use regex::Regex;
+
+#[derive(Debug)]
+struct Rule {
+    regex: Regex,
+    negated: bool,
+    dir_only: bool,
+}
+
+// Patterns of a single .gitignore file. Following git, patterns are
+// relative to the directory of the file and the last matching pattern
+// decides whether a path is ignored.
+#[derive(Debug)]
+pub struct Gitignore {
+    base: PathBuf,
+    rules: Vec<Rule>,
+}
+
+impl Gitignore {
+    pub fn from_dir(dir: &Path) -> Option<Self> {
+        let path = dir.join(".gitignore");
+        if !path.is_file() {
+            return None;
+        }
+
+        match fs::read_to_string(&path) {
+            Ok(content) => Some(Self::parse(dir, &content)),
+            Err(e) => {
+                log::warn!("Failed to read {:?}: {}", path, e);
+                None
+            }
+        }
+    }
+
+    pub fn parse(base: &Path, content: &str) -> Self {
+        let rules = content.lines().filter_map(parse_rule).collect();
+
+        Self {
+            base: base.to_path_buf(),
+            rules,
+        }
+    }
+
+    // `Some(true)` if the path is ignored, `Some(false)` if it is
+    // re-included with `!` and `None` if no pattern matches it
+    pub fn matched(&self, path: &Path, is_dir: bool) -> Option<bool> {
+        let relative = path.strip_prefix(&self.base).ok()?;
+        let relative = relative
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(name) => Some(name.to_string_lossy()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| (is_dir || !rule.dir_only) && rule.regex.is_match(&relative))
+            .map(|rule| !rule.negated)
+    }
+}
+
+// Checks the deepest .gitignore first since it takes precedence
+// over the ones in parent directories
+pub fn is_ignored(ignores: &[Arc<Gitignore>], path: &Path, is_dir: bool) -> bool {
+    ignores
+        .iter()
+        .rev()
+        .find_map(|ignore| ignore.matched(path, is_dir))
+        .unwrap_or(false)
+}
+
+fn parse_rule(line: &str) -> Option<Rule> {
+    if line.starts_with('#') {
+        return None;
+    }
+
+    // Trailing spaces are ignored unless escaped
+    let mut pattern = line.trim_end_matches(['\r', '\n']);
+    while pattern.ends_with(' ') && !pattern.ends_with("\\ ") {
+        pattern = &pattern[..pattern.len() - 1];
+    }
+    if pattern.is_empty() {
+        return None;
+    }
+
+    let negated = pattern.starts_with('!');
+    if negated {
+        pattern = &pattern[1..];
+    }
+
+    let dir_only = pattern.ends_with('/') && !pattern.ends_with("\\/");
+    if dir_only {
+        pattern = pattern.trim_end_matches('/');
+    }
+    if pattern.is_empty() {
+        return None;
+    }
+
+    // A separator at the beginning or in the middle anchors the pattern to
+    // the directory of the .gitignore, otherwise it matches at any depth
+    let anchored = pattern.contains('/');
+    let pattern = pattern.strip_prefix('/').unwrap_or(pattern);
+
+    let mut regex = String::from("^");
+    if !anchored {
+        regex.push_str("(?:.*/)?");
+    }
+    regex.push_str(&glob_to_regex(pattern));
+    regex.push('$');
+
+    match Regex::new(&regex) {
+        Ok(regex) => Some(Rule {
+            regex,
+            negated,
+            dir_only,
+        }),
+        Err(e) => {
+            log::warn!("Invalid gitignore pattern {:?}: {}", line, e);
+            None
+        }
+    }
+}
+
+pub fn glob_to_regex(pattern: &str) -> String {
+    let chars = pattern.chars().collect::<Vec<char>>();
+    let mut regex = String::new();
+
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                let at_start = i == 0 || chars[i - 1] == '/';
+                let at_end = i + 2 == chars.len();
+                let before_slash = chars.get(i + 2) == Some(&'/');
+
+                if at_start && before_slash {
+                    // `**/` matches zero or more directories
+                    regex.push_str("(?:.*/)?");
+                    i += 3;
+                } else if at_start && at_end {
+                    // `/**` matches everything inside
+                    regex.push_str(".*");
+                    i += 2;
+                } else {
+                    regex.push_str("[^/]*");
+                    i += 2;
+                }
+            }
+            '*' => {
+                regex.push_str("[^/]*");
+                i += 1;
+            }
+            '?' => {
+                regex.push_str("[^/]");
+                i += 1;
+            }
+            '[' => match chars[i + 1..].iter().position(|ch| *ch == ']') {
+                Some(len) if len > 0 => {
+                    let class = &chars[i + 1..i + 1 + len];
+                    regex.push('[');
+                    for (j, ch) in class.iter().enumerate() {
+                        match ch {
+                            '!' if j == 0 => regex.push('^'),
+                            // Set operators of the regex crate
+                            '\\' | '[' | '&' | '~' => {
+                                regex.push('\\');
+                                regex.push(*ch);
+                            }
+                            _ => regex.push(*ch),
+                        }
+                    }
+                    regex.push(']');
+                    i += len + 2;
+                }
+                _ => {
+                    regex.push_str("\\[");
+                    i += 1;
+                }
+            },
+            '\\' if i + 1 < chars.len() => {
+                regex.push_str(&regex::escape(&chars[i + 1].to_string()));
+                i += 2;
+            }
+            ch => {
+                regex.push_str(&regex::escape(&ch.to_string()));
+                i += 1;
+            }
+        }
+    }
+
+    regex
+}
@@ -0,0 +1,537 @@
+use std::ops::AddAssign;
+
+use crate::{lexer::LineScan, unsafe_code::UnsafeKind};
+
+// Kinds of Rust items `--units` tells apart
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+    Struct,
+    Enum,
+    Union,
+    Trait,
+    TypeAlias,
+    Fn,
+    Impl,
+    Mod,
+    Const,
+    Static,
+    ExternBlock,
+    Macro,
+}
+
+impl ItemKind {
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            ItemKind::Struct => "struct",
+            ItemKind::Enum => "enum",
+            ItemKind::Union => "union",
+            ItemKind::Trait => "trait",
+            ItemKind::TypeAlias => "type",
+            ItemKind::Fn => "fn",
+            ItemKind::Impl => "impl",
+            ItemKind::Mod => "mod",
+            ItemKind::Const => "const",
+            ItemKind::Static => "static",
+            ItemKind::ExternBlock => "extern",
+            ItemKind::Macro => "macro_rules",
+        }
+    }
+}
+
+// Kinds of functions, told apart by the block they are declared in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FnKind {
+    Free,
+    // In an inherent `impl`
+    Method,
+    // In an `impl Trait for Type`
+    TraitImplMethod,
+    // In a `trait`, without a body
+    Required,
+    // In a `trait`, with a default body
+    Provided,
+    // Marked with `#[test]`, wherever it is
+    Test,
+}
+
+// Number of items of every kind
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Units {
+    pub structs: usize,
+    pub enums: usize,
+    pub unions: usize,
+    pub traits: usize,
+    pub type_aliases: usize,
+    // Every function, whatever its kind
+    pub fns: usize,
+    pub free_fns: usize,
+    pub methods: usize,
+    pub trait_impl_methods: usize,
+    pub required_methods: usize,
+    pub provided_methods: usize,
+    pub test_fns: usize,
+    pub closures: usize,
+    pub impls: usize,
+    pub mods: usize,
+    pub consts: usize,
+    pub statics: usize,
+    pub extern_blocks: usize,
+    pub macros: usize,
+}
+
+impl Units {
+    pub fn add(&mut self, kind: ItemKind) {
+        let count = match kind {
+            ItemKind::Struct => &mut self.structs,
+            ItemKind::Enum => &mut self.enums,
+            ItemKind::Union => &mut self.unions,
+            ItemKind::Trait => &mut self.traits,
+            ItemKind::TypeAlias => &mut self.type_aliases,
+            ItemKind::Fn => &mut self.fns,
+            ItemKind::Impl => &mut self.impls,
+            ItemKind::Mod => &mut self.mods,
+            ItemKind::Const => &mut self.consts,
+            ItemKind::Static => &mut self.statics,
+            ItemKind::ExternBlock => &mut self.extern_blocks,
+            ItemKind::Macro => &mut self.macros,
+        };
+        *count += 1;
+    }
+
+    pub fn add_fn(&mut self, kind: FnKind) {
+        let count = match kind {
+            FnKind::Free => &mut self.free_fns,
+            FnKind::Method => &mut self.methods,
+            FnKind::TraitImplMethod => &mut self.trait_impl_methods,
+            FnKind::Required => &mut self.required_methods,
+            FnKind::Provided => &mut self.provided_methods,
+            FnKind::Test => &mut self.test_fns,
+        };
+        *count += 1;
+    }
+
+    // Label and count of every kind, in the order they are printed
+    pub fn rows(&self) -> [(&'static str, usize); 19] {
+        [
+            ("structs", self.structs),
+            ("enums", self.enums),
+            ("unions", self.unions),
+            ("traits", self.traits),
+            ("type aliases", self.type_aliases),
+            ("functions", self.fns),
+            ("free functions", self.free_fns),
+            ("methods", self.methods),
+            ("trait impl methods", self.trait_impl_methods),
+            ("required methods", self.required_methods),
+            ("provided methods", self.provided_methods),
+            ("test functions", self.test_fns),
+            ("closures", self.closures),
+            ("impl blocks", self.impls),
+            ("modules", self.mods),
+            ("consts", self.consts),
+            ("statics", self.statics),
+            ("extern blocks", self.extern_blocks),
+            ("macros", self.macros),
+        ]
+    }
+}
+
+impl AddAssign<&Units> for Units {
+    fn add_assign(&mut self, other: &Units) {
+        self.structs += other.structs;
+        self.enums += other.enums;
+        self.unions += other.unions;
+        self.traits += other.traits;
+        self.type_aliases += other.type_aliases;
+        self.fns += other.fns;
+        self.free_fns += other.free_fns;
+        self.methods += other.methods;
+        self.trait_impl_methods += other.trait_impl_methods;
+        self.required_methods += other.required_methods;
+        self.provided_methods += other.provided_methods;
+        self.test_fns += other.test_fns;
+        self.closures += other.closures;
+        self.impls += other.impls;
+        self.mods += other.mods;
+        self.consts += other.consts;
+        self.statics += other.statics;
+        self.extern_blocks += other.extern_blocks;
+        self.macros += other.macros;
+    }
+}
+
+fn is_ident(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+// Strips `keyword` if it is a whole word at the start of `line`
+fn keyword<'a>(line: &'a str, keyword: &str) -> Option<&'a str> {
+    let rest = line.strip_prefix(keyword)?;
+    match rest.bytes().next() {
+        Some(b) if is_ident(b) => None,
+        _ => Some(rest.trim_start()),
+    }
+}
+
+// Attributes in front of an item, an attribute which is not closed on
+// the line leaves nothing
+fn skip_attributes(mut line: &str) -> &str {
+    while line.starts_with("#[") {
+        match line.find(']') {
+            Some(end) => line = line[end + 1..].trim_start(),
+            None => return "",
+        }
+    }
+    line
+}
+
+// Line holding nothing but attributes, like `#[derive(Debug)]`
+pub fn is_attribute(line: &str) -> bool {
+    line.starts_with("#[") && skip_attributes(line).is_empty()
+}
+
+// `#[inline]`, `pub`, `pub(crate)`, `pub(in crate::a)` and the like
+fn skip_prefixes(mut line: &str) -> &str {
+    loop {
+        if line.starts_with("#[") {
+            line = skip_attributes(line);
+        } else if let Some(rest) = keyword(line, "pub") {
+            line = match rest.strip_prefix('(') {
+                Some(inner) => match inner.find(')') {
+                    Some(end) => inner[end + 1..].trim_start(),
+                    None => return "",
+                },
+                None => rest,
+            };
+        } else {
+            return line;
+        }
+    }
+}
+
+// Recognizes the item a line of Rust code starts, e.g.
+// `pub(crate) const unsafe fn` or `unsafe extern "C" {`.
+// Only the first line of an item is looked at.
+pub fn parse_item(line: &str) -> Option<ItemKind> {
+    let mut line = skip_prefixes(line.trim_start());
+
+    // Qualifiers which can come in front of the keyword
+    loop {
+        if let Some(rest) = keyword(line, "default")
+            .or_else(|| keyword(line, "async"))
+            .or_else(|| keyword(line, "unsafe"))
+            .or_else(|| keyword(line, "safe"))
+            .or_else(|| keyword(line, "auto"))
+        {
+            line = rest;
+            continue;
+        }
+
+        // `const fn` is a qualifier, `const NAME: T` an item
+        if let Some(rest) = keyword(line, "const") {
+            if ["fn", "unsafe", "async", "extern"]
+                .iter()
+                .any(|next| keyword(rest, next).is_some())
+            {
+                line = rest;
+                continue;
+            }
+            return Some(ItemKind::Const);
+        }
+
+        if let Some(rest) = keyword(line, "extern") {
+            // ABI string is optional
+            let rest = match rest.strip_prefix('"') {
+                Some(abi) => abi.find('"').map_or("", |end| abi[end + 1..].trim_start()),
+                None => rest,
+            };
+            if rest.starts_with('{') {
+                return Some(ItemKind::ExternBlock);
+            }
+            if keyword(rest, "crate").is_some() {
+                return None;
+            }
+            line = rest;
+            continue;
+        }
+
+        break;
+    }
+
+    if line.starts_with("macro_rules!") {
+        return Some(ItemKind::Macro);
+    }
+    // `impl<T>` has no space after the keyword
+    if line.starts_with("impl<") {
+        return Some(ItemKind::Impl);
+    }
+
+    let kinds = [
+        ("fn", ItemKind::Fn),
+        ("struct", ItemKind::Struct),
+        ("enum", ItemKind::Enum),
+        ("union", ItemKind::Union),
+        ("trait", ItemKind::Trait),
+        ("type", ItemKind::TypeAlias),
+        ("impl", ItemKind::Impl),
+        ("mod", ItemKind::Mod),
+        ("static", ItemKind::Static),
+    ];
+    kinds.iter().find_map(|(word, kind)| {
+        let rest = keyword(line, word)?;
+        // `union` is only a keyword in front of a name, `fn(` is a pointer type
+        let named = rest
+            .bytes()
+            .next()
+            .is_some_and(|b| is_ident(b) && !b.is_ascii_digit());
+        (named || *kind == ItemKind::Impl).then_some(*kind)
+    })
+}
+
+// Item with plain `pub` visibility and its name, `pub(crate)` and the
+// like are not part of the public API. `pub mod foo;` is left out, as
+// its docs are usually the `//!` ones in the module's file.
+pub fn parse_public_item(line: &str) -> Option<(ItemKind, &str)> {
+    let rest = keyword(skip_attributes(line.trim_start()), "pub")?;
+    if rest.starts_with('(') {
+        return None;
+    }
+
+    let kind = parse_item(rest)?;
+    let declaration = kind == ItemKind::Mod && rest.trim_end().ends_with(';');
+    let unnamed = matches!(kind, ItemKind::Impl | ItemKind::ExternBlock);
+    if declaration || unnamed || kind == ItemKind::Macro {
+        return None;
+    }
+
+    // Name follows the keyword, `static mut` has one more word in between
+    let mut words = rest
+        .split(|ch: char| !ch.is_alphanumeric() && ch != '_')
+        .filter(|word| !word.is_empty());
+    words.find(|word| *word == kind.keyword());
+    let name = words.find(|word| *word != "mut").unwrap_or("");
+    Some((kind, name))
+}
+
+// Attributes at the start of the line without `#[` and `]`,
+// `#[cfg(test)] mod tests {` gives `cfg(test)`
+fn attributes(mut line: &str) -> Vec<&str> {
+    let mut attributes = vec![];
+    while let Some(attribute) = line.strip_prefix("#[") {
+        let Some(end) = attribute.find(']') else {
+            break;
+        };
+        attributes.push(attribute[..end].trim());
+        line = attribute[end + 1..].trim_start();
+    }
+    attributes
+}
+
+// `#[test]` and `#[tokio::test]` alike
+fn is_test_attribute(attribute: &str) -> bool {
+    attribute == "test" || attribute.ends_with("::test")
+}
+
+// `impl Trait for Type`, as opposed to `impl Type` and `for<'a>` bounds
+fn is_trait_impl(line: &str) -> bool {
+    line.split(|ch: char| !ch.is_alphanumeric() && ch != '_' && ch != '<')
+        .any(|word| word == "for")
+}
+
+// `unsafe` among the qualifiers in front of the keyword of an item
+fn is_unsafe(line: &str, kind: ItemKind) -> bool {
+    skip_prefixes(line.trim_start())
+        .split(|ch: char| !ch.is_alphanumeric() && ch != '_')
+        .take_while(|word| *word != kind.keyword())
+        .any(|word| word == "unsafe")
+}
+
+// `{` of an `unsafe { ... }` block, as opposed to the body of an unsafe
+// function or impl which follow a signature
+fn opens_unsafe_block(line: &str, i: usize) -> bool {
+    line[..i]
+        .trim_end()
+        .strip_suffix("unsafe")
+        .is_some_and(|before| !before.bytes().next_back().is_some_and(is_ident))
+}
+
+// What a line holds besides units
+#[derive(Debug, Default)]
+pub struct LineItems {
+    pub test: bool,
+    // Inside of an unsafe block or opening one
+    pub in_unsafe: bool,
+    pub unsafe_kinds: Vec<UnsafeKind>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    Impl,
+    TraitImpl,
+    Trait,
+    // Function bodies, modules and every other block
+    Other,
+}
+
+// Follows the blocks of a Rust file line by line, so a function can be
+// told apart by the block it is in. Works on the symbols the lexer found
+// in code, braces in strings and comments are not taken for blocks.
+#[derive(Debug, Default)]
+pub struct ItemScanner {
+    scopes: Vec<Scope>,
+    // Opened by the next `{`, set after an `impl` or a `trait`
+    pending: Option<Scope>,
+    // Function in a trait waiting for its body or a `;`
+    trait_fn: bool,
+    // Depth of `(` and `[`, a `;` in `[u8; 4]` does not end an item
+    nesting: usize,
+    // `#[test]` was seen on a line before the item
+    test: bool,
+    // `#[cfg(test)]` or `#[test]` was seen, the next item is test code
+    test_item: bool,
+    // Number of blocks around the test item the scanner is in
+    test_depth: Option<usize>,
+    // `#![cfg(test)]` makes the whole file test code
+    test_file: bool,
+    // Number of blocks around the outermost unsafe block the scanner is in
+    unsafe_depth: Option<usize>,
+    // Last character of code on the previous line
+    last: Option<char>,
+}
+
+impl ItemScanner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Lines of the attributes, doc comments and the body of a test item
+    pub fn in_test(&self) -> bool {
+        self.test_file || self.test_item || self.test_depth.is_some()
+    }
+
+    pub fn scan_line(&mut self, line: &str, scan: &LineScan, units: &mut Units) -> LineItems {
+        // Text of a string continued from an earlier line, like code in a
+        // test fixture, holds neither items nor attributes
+        let in_string = scan.starts_in_string;
+        let code = &line[..scan.code_end(line)];
+        let attributes = if in_string { vec![] } else { attributes(line) };
+        let test = self.test || attributes.iter().any(|name| is_test_attribute(name));
+        self.test_file |= !in_string && line.starts_with("#![cfg(test)]");
+        self.test_item |= attributes
+            .iter()
+            .any(|name| is_test_attribute(name) || *name == "cfg(test)");
+        let mut found = LineItems {
+            test: self.in_test(),
+            in_unsafe: self.unsafe_depth.is_some(),
+            unsafe_kinds: vec![],
+        };
+
+        let item = if in_string { None } else { parse_item(line) };
+        if let Some(kind) = item {
+            units.add(kind);
+
+            let unsafe_kind = match kind {
+                ItemKind::Fn => Some(UnsafeKind::Fn),
+                ItemKind::Impl => Some(UnsafeKind::Impl),
+                ItemKind::Trait => Some(UnsafeKind::Trait),
+                _ => None,
+            };
+            if let Some(unsafe_kind) = unsafe_kind.filter(|_| is_unsafe(line, kind)) {
+                found.unsafe_kinds.push(unsafe_kind);
+            }
+        }
+
+        match item {
+            Some(ItemKind::Fn) if test => units.add_fn(FnKind::Test),
+            Some(ItemKind::Fn) => match self.scopes.last() {
+                Some(Scope::Impl) => units.add_fn(FnKind::Method),
+                Some(Scope::TraitImpl) => units.add_fn(FnKind::TraitImplMethod),
+                // Decided by what comes first, a body or a `;`
+                Some(Scope::Trait) => self.trait_fn = true,
+                _ => units.add_fn(FnKind::Free),
+            },
+            Some(ItemKind::Impl) if is_trait_impl(code) => self.pending = Some(Scope::TraitImpl),
+            Some(ItemKind::Impl) => self.pending = Some(Scope::Impl),
+            // `for Type` of a signature wrapped by rustfmt
+            None if !in_string && self.pending == Some(Scope::Impl) && is_trait_impl(code) => {
+                self.pending = Some(Scope::TraitImpl)
+            }
+            Some(ItemKind::Trait) => self.pending = Some(Scope::Trait),
+            _ => {}
+        }
+
+        // Attributes are kept for the item on the next lines
+        self.test = test && item.is_none();
+
+        let bytes = line.as_bytes();
+        for &i in &scan.symbols {
+            match bytes[i] {
+                b'(' | b'[' => self.nesting += 1,
+                b')' | b']' => self.nesting = self.nesting.saturating_sub(1),
+                b'{' => {
+                    if self.trait_fn && self.nesting == 0 {
+                        units.add_fn(FnKind::Provided);
+                        self.trait_fn = false;
+                    }
+                    if self.test_item && self.test_depth.is_none() {
+                        self.test_depth = Some(self.scopes.len());
+                    }
+                    self.test_item = false;
+
+                    if opens_unsafe_block(line, i) {
+                        found.unsafe_kinds.push(UnsafeKind::Block);
+                        found.in_unsafe = true;
+                        if self.unsafe_depth.is_none() {
+                            self.unsafe_depth = Some(self.scopes.len());
+                        }
+                    }
+
+                    let scope = self.pending.take().unwrap_or(Scope::Other);
+                    self.scopes.push(scope);
+                }
+                b'}' => {
+                    self.scopes.pop();
+                    if self.test_depth == Some(self.scopes.len()) {
+                        self.test_depth = None;
+                    }
+                    if self.unsafe_depth == Some(self.scopes.len()) {
+                        self.unsafe_depth = None;
+                    }
+                }
+                b';' if self.nesting == 0 => {
+                    if self.trait_fn {
+                        units.add_fn(FnKind::Required);
+                        self.trait_fn = false;
+                    }
+                    self.pending = None;
+                    // Item without a body, like `#[cfg(test)] use super::*;`
+                    self.test_item = false;
+                }
+                b'|' if self.opens_closure(line, i) => units.closures += 1,
+                _ => {}
+            }
+        }
+
+        self.last = code.trim_end().chars().next_back();
+        found
+    }
+
+    // A `|` opens the parameters of a closure where an expression starts,
+    // anywhere else it is a binary or or a pattern alternative
+    fn opens_closure(&self, line: &str, i: usize) -> bool {
+        let before = line[..i].trim_end();
+        if before.is_empty() {
+            // Closure passed as an argument on a line of its own
+            return matches!(self.last, Some('(' | ','));
+        }
+
+        let word = before
+            .rsplit(|ch: char| !ch.is_alphanumeric() && ch != '_')
+            .next()
+            .unwrap_or("");
+        before.ends_with(['(', ',', '=', '{', ':'])
+            || before.ends_with("=>")
+            || word == "move"
+            || word == "return"
+    }
+}
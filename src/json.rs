@@ -0,0 +1,370 @@
+use std::{collections::BTreeMap, path::Path};
+
+use serde::Serialize;
+
+use crate::{
+    category::Categories,
+    doc_coverage::{coverage, UndocumentedItem},
+    file_stats::{display_path, FileStats, SkippedFile},
+    items::Units,
+    summary::Summary,
+    tags::TagMatch,
+    tree::DirNode,
+    unsafe_code::UnsafeMatch,
+    workspace::CrateStats,
+};
+
+// Bump whenever a field is renamed, removed or changes its type.
+// Adding new fields is not a breaking change.
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize, Debug)]
+pub struct JsonReport {
+    pub schema_version: u32,
+    pub loc: usize,
+    // Blank lines are counted even if they are not part of `loc`
+    pub blanks: usize,
+    // Physical lines of every counted file
+    pub lines: usize,
+    pub todo: usize,
+    pub fixme: usize,
+    // Every tag looked for, TODO and FIXME included
+    pub tag_counts: BTreeMap<String, usize>,
+    pub owners: BTreeMap<String, usize>,
+    // 0 unless `--stale` is passed
+    pub stale: usize,
+    pub units: JsonUnits,
+    // Lines of code, `--exclude-tests` does not change them
+    pub categories: JsonCategories,
+    pub ratio: JsonRatio,
+    pub total: JsonSummary,
+    pub languages: Vec<JsonLanguage>,
+    // Empty unless `--files` is passed
+    pub files: Vec<JsonFile>,
+    // `null` unless `--tree` is passed
+    pub tree: Option<JsonDir>,
+    pub skipped: Vec<JsonSkipped>,
+    // Empty unless `--list-tags` is passed
+    pub tags: Vec<JsonTag>,
+    // `null` unless `--doc-coverage` is passed
+    pub doc_coverage: Option<JsonDocCoverage>,
+    // `null` unless `--workspace` is passed
+    pub workspace: Option<JsonWorkspace>,
+    #[serde(rename = "unsafe")]
+    pub unsafe_code: JsonUnsafe,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct JsonUnits {
+    pub structs: usize,
+    pub enums: usize,
+    pub unions: usize,
+    pub traits: usize,
+    pub type_aliases: usize,
+    pub functions: usize,
+    pub free_functions: usize,
+    pub methods: usize,
+    pub trait_impl_methods: usize,
+    pub required_methods: usize,
+    pub provided_methods: usize,
+    pub test_functions: usize,
+    pub closures: usize,
+    pub impl_blocks: usize,
+    pub modules: usize,
+    pub consts: usize,
+    pub statics: usize,
+    pub extern_blocks: usize,
+    pub macros: usize,
+}
+
+impl From<&Units> for JsonUnits {
+    fn from(units: &Units) -> Self {
+        Self {
+            structs: units.structs,
+            enums: units.enums,
+            unions: units.unions,
+            traits: units.traits,
+            type_aliases: units.type_aliases,
+            functions: units.fns,
+            free_functions: units.free_fns,
+            methods: units.methods,
+            trait_impl_methods: units.trait_impl_methods,
+            required_methods: units.required_methods,
+            provided_methods: units.provided_methods,
+            test_functions: units.test_fns,
+            closures: units.closures,
+            impl_blocks: units.impls,
+            modules: units.mods,
+            consts: units.consts,
+            statics: units.statics,
+            extern_blocks: units.extern_blocks,
+            macros: units.macros,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct JsonCategories {
+    pub production: usize,
+    pub test: usize,
+    pub example: usize,
+    pub bench: usize,
+}
+
+impl From<&Categories> for JsonCategories {
+    fn from(categories: &Categories) -> Self {
+        Self {
+            production: categories.production,
+            test: categories.test,
+            example: categories.example,
+            bench: categories.bench,
+        }
+    }
+}
+
+// Percentages in 0.0..=100.0
+#[derive(Serialize, Debug)]
+pub struct JsonRatio {
+    pub loc: f64,
+    pub comments: f64,
+    pub docs: f64,
+    pub blanks: f64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct JsonSummary {
+    pub files: usize,
+    pub code: usize,
+    pub mixed: usize,
+    pub mixed_docs: usize,
+    pub comments: usize,
+    pub docs: usize,
+    pub blanks: usize,
+    pub total: usize,
+}
+
+#[derive(Serialize, Debug)]
+pub struct JsonLanguage {
+    pub language: &'static str,
+    #[serde(flatten)]
+    pub summary: JsonSummary,
+}
+
+#[derive(Serialize, Debug)]
+pub struct JsonUnsafe {
+    pub blocks: usize,
+    pub functions: usize,
+    pub impls: usize,
+    pub traits: usize,
+    pub lines: usize,
+    // Empty unless `--unsafe` is passed
+    pub locations: Vec<JsonUnsafeMatch>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct JsonUnsafeMatch {
+    pub path: String,
+    pub line: usize,
+    pub kind: &'static str,
+}
+
+impl JsonUnsafeMatch {
+    pub fn new(found: &UnsafeMatch, root: &Path) -> Self {
+        Self {
+            path: found.location.display_path(root),
+            line: found.location.line,
+            kind: found.kind.name(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct JsonWorkspace {
+    pub crates: Vec<JsonCrate>,
+    pub total: JsonSummary,
+}
+
+#[derive(Serialize, Debug)]
+pub struct JsonCrate {
+    pub name: String,
+    pub path: String,
+    #[serde(flatten)]
+    pub summary: JsonSummary,
+}
+
+impl JsonCrate {
+    pub fn new(stats: &CrateStats, root: &Path) -> Self {
+        Self {
+            name: stats.name.clone(),
+            // The root package has no path of its own below the root
+            path: if stats.path == root {
+                ".".to_string()
+            } else {
+                display_path(&stats.path, root)
+            },
+            summary: JsonSummary::from(&stats.summary),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct JsonFile {
+    pub path: String,
+    pub language: &'static str,
+    pub code: usize,
+    pub mixed: usize,
+    pub mixed_docs: usize,
+    pub comments: usize,
+    pub docs: usize,
+    pub blanks: usize,
+    pub units: JsonUnits,
+}
+
+#[derive(Serialize, Debug)]
+pub struct JsonDir {
+    pub name: String,
+    #[serde(flatten)]
+    pub summary: JsonSummary,
+    pub children: Vec<JsonDir>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct JsonSkipped {
+    pub path: String,
+    pub reason: String,
+}
+
+impl JsonSkipped {
+    pub fn new(skipped: &SkippedFile, root: &Path) -> Self {
+        Self {
+            path: display_path(&skipped.path, root),
+            reason: skipped.reason.to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct JsonTag {
+    pub path: String,
+    pub line: usize,
+    pub tag: String,
+    pub owner: Option<String>,
+    pub issue: Option<String>,
+    pub date: Option<String>,
+    pub text: String,
+    pub stale: bool,
+}
+
+impl JsonTag {
+    pub fn new(tag: &TagMatch, root: &Path) -> Self {
+        Self {
+            path: tag.location.display_path(root),
+            line: tag.location.line,
+            tag: tag.tag.clone(),
+            owner: tag.meta.owner.clone(),
+            issue: tag.meta.issue.clone(),
+            date: tag.meta.date.clone(),
+            text: tag.text.clone(),
+            stale: tag.stale,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct JsonDocCoverage {
+    pub public_items: usize,
+    pub documented_items: usize,
+    // Percentage in 0.0..=100.0
+    pub coverage: f64,
+    // Only files with public items
+    pub files: Vec<JsonFileCoverage>,
+    pub undocumented: Vec<JsonUndocumented>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct JsonFileCoverage {
+    pub path: String,
+    pub public_items: usize,
+    pub documented_items: usize,
+    pub coverage: f64,
+}
+
+impl JsonFileCoverage {
+    pub fn new(file: &FileStats, root: &Path) -> Self {
+        Self {
+            path: file.display_path(root),
+            public_items: file.public_items,
+            documented_items: file.documented_items,
+            coverage: coverage(file.documented_items, file.public_items),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct JsonUndocumented {
+    pub path: String,
+    pub line: usize,
+    pub kind: &'static str,
+    pub name: String,
+}
+
+impl JsonUndocumented {
+    pub fn new(item: &UndocumentedItem, root: &Path) -> Self {
+        Self {
+            path: item.location.display_path(root),
+            line: item.location.line,
+            kind: item.kind.keyword(),
+            name: item.name.clone(),
+        }
+    }
+}
+
+impl From<&Summary> for JsonSummary {
+    fn from(summary: &Summary) -> Self {
+        Self {
+            files: summary.files,
+            code: summary.code,
+            mixed: summary.mixed,
+            mixed_docs: summary.mixed_docs,
+            comments: summary.comments,
+            docs: summary.docs,
+            blanks: summary.blanks,
+            total: summary.total(),
+        }
+    }
+}
+
+impl JsonFile {
+    pub fn new(file: &FileStats, root: &Path) -> Self {
+        Self {
+            path: file.display_path(root),
+            language: file.language,
+            code: file.code,
+            mixed: file.mixed,
+            mixed_docs: file.mixed_docs,
+            comments: file.comments,
+            docs: file.docs,
+            blanks: file.blanks,
+            units: JsonUnits::from(&file.units),
+        }
+    }
+}
+
+impl JsonDir {
+    pub fn new(dir: &DirNode, depth: usize, max_depth: Option<usize>) -> Self {
+        let children = if max_depth.is_some_and(|max| depth >= max) {
+            vec![]
+        } else {
+            dir.children
+                .values()
+                .map(|child| JsonDir::new(child, depth + 1, max_depth))
+                .collect()
+        };
+
+        Self {
+            name: dir.name.clone(),
+            summary: JsonSummary::from(&dir.summary),
+            children,
+        }
+    }
+}
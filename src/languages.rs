@@ -0,0 +1,684 @@
+use std::path::Path;
+
+pub struct Language {
+    pub name: &'static str,
+    pub extensions: &'static [&'static str],
+    // Whole file names for files without an extension of their own
+    pub filenames: &'static [&'static str],
+    pub line_comments: &'static [&'static str],
+    pub block_comments: &'static [(&'static str, &'static str)],
+    pub doc_line_comments: &'static [&'static str],
+    pub doc_block_comments: &'static [(&'static str, &'static str)],
+    // Strings which end together with the line
+    pub quotes: &'static [(&'static str, &'static str)],
+    // Strings which may span several lines
+    pub multi_line_quotes: &'static [(&'static str, &'static str)],
+    pub nested: bool,
+}
+
+const C_BLOCK: &[(&str, &str)] = &[("/*", "*/")];
+const JAVADOC_BLOCK: &[(&str, &str)] = &[("/**", "*/")];
+
+const DQ: &[(&str, &str)] = &[("\"", "\"")];
+const DQ_SQ: &[(&str, &str)] = &[("\"", "\""), ("'", "'")];
+const TRIPLE_DQ: &[(&str, &str)] = &[("\"\"\"", "\"\"\"")];
+const TRIPLE_QUOTES: &[(&str, &str)] = &[("\"\"\"", "\"\"\""), ("'''", "'''")];
+const BACKTICK: &[(&str, &str)] = &[("`", "`")];
+
+// Used for files whose extension is not in the table.
+// Every non-empty line is counted as code.
+pub const PLAIN_TEXT: Language = Language {
+    name: "Plain Text",
+    extensions: &[],
+    filenames: &[],
+    line_comments: &[],
+    block_comments: &[],
+    doc_line_comments: &[],
+    doc_block_comments: &[],
+    quotes: &[],
+    multi_line_quotes: &[],
+    nested: false,
+};
+
+pub const LANGUAGES: &[Language] = &[
+    Language {
+        name: "Rust",
+        extensions: &["rs"],
+        filenames: &[],
+        line_comments: &["//"],
+        block_comments: C_BLOCK,
+        doc_line_comments: &["///", "//!"],
+        doc_block_comments: &[("/**", "*/"), ("/*!", "*/")],
+        quotes: &[],
+        multi_line_quotes: DQ,
+        nested: true,
+    },
+    Language {
+        name: "C",
+        extensions: &["c"],
+        filenames: &[],
+        line_comments: &["//"],
+        block_comments: C_BLOCK,
+        doc_line_comments: &[],
+        doc_block_comments: &[],
+        quotes: DQ_SQ,
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "C Header",
+        extensions: &["h"],
+        filenames: &[],
+        line_comments: &["//"],
+        block_comments: C_BLOCK,
+        doc_line_comments: &[],
+        doc_block_comments: &[],
+        quotes: DQ_SQ,
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "C++",
+        extensions: &["cpp", "cc", "cxx", "c++", "hpp", "hh", "hxx"],
+        filenames: &[],
+        line_comments: &["//"],
+        block_comments: C_BLOCK,
+        doc_line_comments: &["///", "//!"],
+        doc_block_comments: &[("/**", "*/"), ("/*!", "*/")],
+        quotes: DQ_SQ,
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "C#",
+        extensions: &["cs"],
+        filenames: &[],
+        line_comments: &["//"],
+        block_comments: C_BLOCK,
+        doc_line_comments: &["///"],
+        doc_block_comments: JAVADOC_BLOCK,
+        quotes: DQ_SQ,
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "Objective-C",
+        extensions: &["m", "mm"],
+        filenames: &[],
+        line_comments: &["//"],
+        block_comments: C_BLOCK,
+        doc_line_comments: &["///"],
+        doc_block_comments: JAVADOC_BLOCK,
+        quotes: DQ_SQ,
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "Java",
+        extensions: &["java"],
+        filenames: &[],
+        line_comments: &["//"],
+        block_comments: C_BLOCK,
+        doc_line_comments: &[],
+        doc_block_comments: JAVADOC_BLOCK,
+        quotes: DQ_SQ,
+        multi_line_quotes: TRIPLE_DQ,
+        nested: false,
+    },
+    Language {
+        name: "Kotlin",
+        extensions: &["kt", "kts"],
+        filenames: &[],
+        line_comments: &["//"],
+        block_comments: C_BLOCK,
+        doc_line_comments: &[],
+        doc_block_comments: JAVADOC_BLOCK,
+        quotes: DQ_SQ,
+        multi_line_quotes: TRIPLE_DQ,
+        nested: true,
+    },
+    Language {
+        name: "Scala",
+        extensions: &["scala", "sc"],
+        filenames: &[],
+        line_comments: &["//"],
+        block_comments: C_BLOCK,
+        doc_line_comments: &[],
+        doc_block_comments: JAVADOC_BLOCK,
+        quotes: DQ_SQ,
+        multi_line_quotes: TRIPLE_DQ,
+        nested: true,
+    },
+    Language {
+        name: "Groovy",
+        extensions: &["groovy", "gradle"],
+        filenames: &[],
+        line_comments: &["//"],
+        block_comments: C_BLOCK,
+        doc_line_comments: &[],
+        doc_block_comments: JAVADOC_BLOCK,
+        quotes: DQ_SQ,
+        multi_line_quotes: TRIPLE_QUOTES,
+        nested: false,
+    },
+    Language {
+        name: "JavaScript",
+        extensions: &["js", "mjs", "cjs", "jsx"],
+        filenames: &[],
+        line_comments: &["//"],
+        block_comments: C_BLOCK,
+        doc_line_comments: &[],
+        doc_block_comments: JAVADOC_BLOCK,
+        quotes: DQ_SQ,
+        multi_line_quotes: BACKTICK,
+        nested: false,
+    },
+    Language {
+        name: "TypeScript",
+        extensions: &["ts", "mts", "cts", "tsx"],
+        filenames: &[],
+        line_comments: &["//"],
+        block_comments: C_BLOCK,
+        doc_line_comments: &[],
+        doc_block_comments: JAVADOC_BLOCK,
+        quotes: DQ_SQ,
+        multi_line_quotes: BACKTICK,
+        nested: false,
+    },
+    Language {
+        name: "Go",
+        extensions: &["go"],
+        filenames: &[],
+        line_comments: &["//"],
+        block_comments: C_BLOCK,
+        doc_line_comments: &[],
+        doc_block_comments: &[],
+        quotes: DQ_SQ,
+        multi_line_quotes: BACKTICK,
+        nested: false,
+    },
+    Language {
+        name: "Swift",
+        extensions: &["swift"],
+        filenames: &[],
+        line_comments: &["//"],
+        block_comments: C_BLOCK,
+        doc_line_comments: &["///"],
+        doc_block_comments: JAVADOC_BLOCK,
+        quotes: DQ,
+        multi_line_quotes: TRIPLE_DQ,
+        nested: true,
+    },
+    Language {
+        name: "Dart",
+        extensions: &["dart"],
+        filenames: &[],
+        line_comments: &["//"],
+        block_comments: C_BLOCK,
+        doc_line_comments: &["///"],
+        doc_block_comments: JAVADOC_BLOCK,
+        quotes: DQ_SQ,
+        multi_line_quotes: TRIPLE_QUOTES,
+        nested: true,
+    },
+    Language {
+        name: "Zig",
+        extensions: &["zig"],
+        filenames: &[],
+        line_comments: &["//"],
+        block_comments: &[],
+        doc_line_comments: &["///", "//!"],
+        doc_block_comments: &[],
+        quotes: DQ_SQ,
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "PHP",
+        extensions: &["php"],
+        filenames: &[],
+        line_comments: &["//", "#"],
+        block_comments: C_BLOCK,
+        doc_line_comments: &[],
+        doc_block_comments: JAVADOC_BLOCK,
+        quotes: DQ_SQ,
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "CSS",
+        extensions: &["css"],
+        filenames: &[],
+        line_comments: &[],
+        block_comments: C_BLOCK,
+        doc_line_comments: &[],
+        doc_block_comments: &[],
+        quotes: DQ_SQ,
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "SCSS",
+        extensions: &["scss", "sass", "less"],
+        filenames: &[],
+        line_comments: &["//"],
+        block_comments: C_BLOCK,
+        doc_line_comments: &["///"],
+        doc_block_comments: &[],
+        quotes: DQ_SQ,
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "Python",
+        extensions: &["py", "pyw", "pyi"],
+        filenames: &[],
+        line_comments: &["#"],
+        block_comments: &[],
+        doc_line_comments: &[],
+        doc_block_comments: &[],
+        quotes: DQ_SQ,
+        multi_line_quotes: TRIPLE_QUOTES,
+        nested: false,
+    },
+    Language {
+        name: "Ruby",
+        extensions: &["rb", "rake", "gemspec"],
+        filenames: &[],
+        line_comments: &["#"],
+        block_comments: &[("=begin", "=end")],
+        doc_line_comments: &[],
+        doc_block_comments: &[],
+        quotes: DQ_SQ,
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "Perl",
+        extensions: &["pl", "pm"],
+        filenames: &[],
+        line_comments: &["#"],
+        block_comments: &[("=pod", "=cut")],
+        doc_line_comments: &[],
+        doc_block_comments: &[],
+        quotes: DQ_SQ,
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "Shell",
+        extensions: &["sh", "bash", "zsh", "fish", "ksh"],
+        filenames: &[],
+        line_comments: &["#"],
+        block_comments: &[],
+        doc_line_comments: &[],
+        doc_block_comments: &[],
+        quotes: DQ_SQ,
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "PowerShell",
+        extensions: &["ps1", "psm1", "psd1"],
+        filenames: &[],
+        line_comments: &["#"],
+        block_comments: &[("<#", "#>")],
+        doc_line_comments: &[],
+        doc_block_comments: &[],
+        quotes: DQ_SQ,
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "R",
+        extensions: &["r", "R"],
+        filenames: &[],
+        line_comments: &["#"],
+        block_comments: &[],
+        doc_line_comments: &["#'"],
+        doc_block_comments: &[],
+        quotes: DQ_SQ,
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "Julia",
+        extensions: &["jl"],
+        filenames: &[],
+        line_comments: &["#"],
+        block_comments: &[("#=", "=#")],
+        doc_line_comments: &[],
+        doc_block_comments: &[],
+        quotes: DQ,
+        multi_line_quotes: TRIPLE_DQ,
+        nested: true,
+    },
+    Language {
+        name: "Elixir",
+        extensions: &["ex", "exs"],
+        filenames: &[],
+        line_comments: &["#"],
+        block_comments: &[],
+        doc_line_comments: &[],
+        doc_block_comments: &[],
+        quotes: DQ_SQ,
+        multi_line_quotes: TRIPLE_DQ,
+        nested: false,
+    },
+    Language {
+        name: "Nim",
+        extensions: &["nim"],
+        filenames: &[],
+        line_comments: &["#"],
+        block_comments: &[("#[", "]#")],
+        doc_line_comments: &["##"],
+        doc_block_comments: &[("##[", "]##")],
+        quotes: DQ,
+        multi_line_quotes: TRIPLE_DQ,
+        nested: true,
+    },
+    Language {
+        name: "Makefile",
+        extensions: &["mk", "mak"],
+        filenames: &["Makefile", "makefile", "GNUmakefile"],
+        line_comments: &["#"],
+        block_comments: &[],
+        doc_line_comments: &[],
+        doc_block_comments: &[],
+        quotes: &[],
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "Dockerfile",
+        extensions: &["dockerfile"],
+        filenames: &["Dockerfile"],
+        line_comments: &["#"],
+        block_comments: &[],
+        doc_line_comments: &[],
+        doc_block_comments: &[],
+        quotes: &[],
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "YAML",
+        extensions: &["yml", "yaml"],
+        filenames: &[],
+        line_comments: &["#"],
+        block_comments: &[],
+        doc_line_comments: &[],
+        doc_block_comments: &[],
+        quotes: DQ_SQ,
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "TOML",
+        extensions: &["toml"],
+        filenames: &[],
+        line_comments: &["#"],
+        block_comments: &[],
+        doc_line_comments: &[],
+        doc_block_comments: &[],
+        quotes: DQ_SQ,
+        multi_line_quotes: TRIPLE_QUOTES,
+        nested: false,
+    },
+    Language {
+        name: "Lua",
+        extensions: &["lua"],
+        filenames: &[],
+        line_comments: &["--"],
+        block_comments: &[("--[[", "]]")],
+        doc_line_comments: &["---"],
+        doc_block_comments: &[],
+        quotes: DQ_SQ,
+        multi_line_quotes: &[("[[", "]]")],
+        nested: false,
+    },
+    Language {
+        name: "SQL",
+        extensions: &["sql"],
+        filenames: &[],
+        line_comments: &["--"],
+        block_comments: C_BLOCK,
+        doc_line_comments: &[],
+        doc_block_comments: &[],
+        quotes: DQ_SQ,
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "Haskell",
+        extensions: &["hs", "lhs"],
+        filenames: &[],
+        line_comments: &["--"],
+        block_comments: &[("{-", "-}")],
+        doc_line_comments: &["-- |", "-- ^"],
+        doc_block_comments: &[("{-|", "-}")],
+        quotes: DQ,
+        multi_line_quotes: &[],
+        nested: true,
+    },
+    Language {
+        name: "Elm",
+        extensions: &["elm"],
+        filenames: &[],
+        line_comments: &["--"],
+        block_comments: &[("{-", "-}")],
+        doc_line_comments: &[],
+        doc_block_comments: &[("{-|", "-}")],
+        quotes: DQ,
+        multi_line_quotes: TRIPLE_DQ,
+        nested: true,
+    },
+    Language {
+        name: "Ada",
+        extensions: &["ada", "adb", "ads"],
+        filenames: &[],
+        line_comments: &["--"],
+        block_comments: &[],
+        doc_line_comments: &[],
+        doc_block_comments: &[],
+        quotes: DQ,
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "OCaml",
+        extensions: &["ml", "mli"],
+        filenames: &[],
+        line_comments: &[],
+        block_comments: &[("(*", "*)")],
+        doc_line_comments: &[],
+        doc_block_comments: &[("(**", "*)")],
+        quotes: DQ,
+        multi_line_quotes: &[],
+        nested: true,
+    },
+    Language {
+        name: "F#",
+        extensions: &["fs", "fsi", "fsx"],
+        filenames: &[],
+        line_comments: &["//"],
+        block_comments: &[("(*", "*)")],
+        doc_line_comments: &["///"],
+        doc_block_comments: &[],
+        quotes: DQ,
+        multi_line_quotes: TRIPLE_DQ,
+        nested: false,
+    },
+    Language {
+        name: "Erlang",
+        extensions: &["erl", "hrl"],
+        filenames: &[],
+        line_comments: &["%"],
+        block_comments: &[],
+        doc_line_comments: &["%%%"],
+        doc_block_comments: &[],
+        quotes: DQ,
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "TeX",
+        extensions: &["tex", "sty", "cls"],
+        filenames: &[],
+        line_comments: &["%"],
+        block_comments: &[],
+        doc_line_comments: &[],
+        doc_block_comments: &[],
+        quotes: &[],
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "Clojure",
+        extensions: &["clj", "cljs", "cljc", "edn"],
+        filenames: &[],
+        line_comments: &[";"],
+        block_comments: &[],
+        doc_line_comments: &[],
+        doc_block_comments: &[],
+        quotes: DQ,
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "Lisp",
+        extensions: &["lisp", "lsp", "el", "scm", "ss", "rkt"],
+        filenames: &[],
+        line_comments: &[";"],
+        block_comments: &[("#|", "|#")],
+        doc_line_comments: &[],
+        doc_block_comments: &[],
+        quotes: DQ,
+        multi_line_quotes: &[],
+        nested: true,
+    },
+    Language {
+        name: "Assembly",
+        extensions: &["asm", "s", "S"],
+        filenames: &[],
+        line_comments: &[";"],
+        block_comments: &[],
+        doc_line_comments: &[],
+        doc_block_comments: &[],
+        quotes: DQ_SQ,
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "Fortran",
+        extensions: &["f90", "f95", "f03", "f08"],
+        filenames: &[],
+        line_comments: &["!"],
+        block_comments: &[],
+        doc_line_comments: &[],
+        doc_block_comments: &[],
+        quotes: DQ_SQ,
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "Pascal",
+        extensions: &["pas", "pp"],
+        filenames: &[],
+        line_comments: &["//"],
+        block_comments: &[("{", "}"), ("(*", "*)")],
+        doc_line_comments: &[],
+        doc_block_comments: &[],
+        quotes: &[("'", "'")],
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "Visual Basic",
+        extensions: &["vb", "vbs", "bas"],
+        filenames: &[],
+        line_comments: &["'"],
+        block_comments: &[],
+        doc_line_comments: &["'''"],
+        doc_block_comments: &[],
+        quotes: DQ,
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "Vim Script",
+        extensions: &["vim"],
+        filenames: &[],
+        line_comments: &["\""],
+        block_comments: &[],
+        doc_line_comments: &[],
+        doc_block_comments: &[],
+        quotes: &[("'", "'")],
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "HTML",
+        extensions: &["html", "htm", "xhtml", "vue", "svelte"],
+        filenames: &[],
+        line_comments: &[],
+        block_comments: &[("<!--", "-->")],
+        doc_line_comments: &[],
+        doc_block_comments: &[],
+        quotes: &[],
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "XML",
+        extensions: &["xml", "xsd", "xsl", "svg"],
+        filenames: &[],
+        line_comments: &[],
+        block_comments: &[("<!--", "-->")],
+        doc_line_comments: &[],
+        doc_block_comments: &[],
+        quotes: &[],
+        multi_line_quotes: &[],
+        nested: false,
+    },
+    Language {
+        name: "Markdown",
+        extensions: &["md", "markdown"],
+        filenames: &[],
+        line_comments: &[],
+        block_comments: &[("<!--", "-->")],
+        doc_line_comments: &[],
+        doc_block_comments: &[],
+        quotes: &[],
+        multi_line_quotes: &[],
+        nested: false,
+    },
+];
+
+impl Language {
+    // File names are looked at before extensions, so `Makefile` is
+    // found like `rules.mk`
+    pub fn from_path(path: &Path) -> &'static Language {
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+        if let Some(lang) = LANGUAGES.iter().find(|lang| lang.filenames.contains(&name)) {
+            return lang;
+        }
+
+        let ext = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default();
+
+        LANGUAGES
+            .iter()
+            .find(|lang| lang.extensions.contains(&ext))
+            .unwrap_or(&PLAIN_TEXT)
+    }
+
+    pub fn is_rust(&self) -> bool {
+        self.name == "Rust"
+    }
+}
@@ -0,0 +1,354 @@
+use std::ops::Range;
+
+use crate::languages::Language;
+
+#[derive(Default, Debug, Clone)]
+pub struct LineScan {
+    pub code: bool,
+    pub doc: bool,
+    // Byte ranges of the comments on the line, markers included
+    pub comments: Vec<Range<usize>>,
+    // Indices of the brackets, `;` and `|` outside of strings and
+    // comments, which is all the Rust unit detection needs to follow blocks
+    pub symbols: Vec<usize>,
+    // The line begins inside of a string opened on an earlier line
+    pub starts_in_string: bool,
+}
+
+impl LineScan {
+    pub fn has_comment(&self) -> bool {
+        !self.comments.is_empty()
+    }
+
+    // End of the code on the line, before a trailing comment
+    pub fn code_end(&self, line: &str) -> usize {
+        self.comments
+            .last()
+            .filter(|comment| comment.end == line.len())
+            .map_or(line.len(), |comment| comment.start)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum State {
+    Code,
+    Str {
+        close: &'static str,
+        multi_line: bool,
+    },
+    RawStr(usize),
+    Block {
+        open: &'static str,
+        close: &'static str,
+        doc: bool,
+        depth: usize,
+    },
+}
+
+// Tokenizer which only knows as much of the grammar as is needed to tell
+// code apart from comments. Comment markers and quotes come from the
+// language table, strings are skipped so comment markers inside them are
+// not taken for real ones. Rust additionally gets raw string, byte string
+// and char literals. State is kept between lines, so strings and block
+// comments spanning several lines are handled as well.
+pub struct Lexer {
+    language: &'static Language,
+    state: State,
+}
+
+fn is_ident_start(b: u8) -> bool {
+    b.is_ascii_alphabetic() || b == b'_'
+}
+
+fn is_ident(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+// Doc markers usually extend plain ones by one character, so repeating
+// that character turns them back into plain comments (`////`, `/***`).
+fn is_doc_marker(bytes: &[u8], i: usize, marker: &str) -> bool {
+    let marker = marker.as_bytes();
+    bytes[i..].starts_with(marker) && bytes.get(i + marker.len()) != marker.last()
+}
+
+impl Lexer {
+    pub fn new(language: &'static Language) -> Self {
+        Self {
+            language,
+            state: State::Code,
+        }
+    }
+
+    pub fn scan_line(&mut self, line: &str) -> LineScan {
+        let bytes = line.as_bytes();
+        let mut scan = LineScan {
+            starts_in_string: matches!(self.state, State::Str { .. } | State::RawStr(_)),
+            ..Default::default()
+        };
+        // Start of a block comment which is not closed yet
+        let mut open_comment = match self.state {
+            State::Block { .. } => Some(0),
+            _ => None,
+        };
+
+        let mut i = 0;
+        while i < bytes.len() {
+            match self.state {
+                State::Str { close, .. } => {
+                    scan.code = true;
+                    if bytes[i] == b'\\' {
+                        i += 2;
+                    } else if bytes[i..].starts_with(close.as_bytes()) {
+                        self.state = State::Code;
+                        i += close.len();
+                    } else {
+                        i += 1;
+                    }
+                }
+                State::RawStr(hashes) => {
+                    scan.code = true;
+                    if bytes[i] == b'"'
+                        && bytes.len() > i + hashes
+                        && bytes[i + 1..=i + hashes].iter().all(|b| *b == b'#')
+                    {
+                        self.state = State::Code;
+                        i += 1 + hashes;
+                    } else {
+                        i += 1;
+                    }
+                }
+                State::Block {
+                    open,
+                    close,
+                    doc,
+                    depth,
+                } => {
+                    if doc && !bytes[i].is_ascii_whitespace() {
+                        scan.doc = true;
+                    }
+
+                    if bytes[i..].starts_with(close.as_bytes()) {
+                        if depth == 1 {
+                            if let Some(start) = open_comment.take() {
+                                scan.comments.push(start..i + close.len());
+                            }
+                        }
+                        self.state = if depth == 1 {
+                            State::Code
+                        } else {
+                            State::Block {
+                                open,
+                                close,
+                                doc,
+                                depth: depth - 1,
+                            }
+                        };
+                        i += close.len();
+                    } else if self.language.nested && bytes[i..].starts_with(open.as_bytes()) {
+                        self.state = State::Block {
+                            open,
+                            close,
+                            doc,
+                            depth: depth + 1,
+                        };
+                        i += open.len();
+                    } else {
+                        i += 1;
+                    }
+                }
+                State::Code => {
+                    if bytes[i].is_ascii_whitespace() {
+                        i += 1;
+                        continue;
+                    }
+
+                    if let Some(len) = self.comment_start(bytes, i, &mut scan) {
+                        if len == 0 {
+                            // Line comment, the rest of the line belongs to it
+                            scan.comments.push(i..bytes.len());
+                            break;
+                        }
+                        open_comment = Some(i);
+                        i += len;
+                        continue;
+                    }
+
+                    scan.code = true;
+                    if b"{}()[];|".contains(&bytes[i]) {
+                        scan.symbols.push(i);
+                    }
+                    i = self.literal(line, i);
+                }
+            }
+        }
+
+        if let Some(start) = open_comment {
+            scan.comments.push(start..bytes.len());
+        }
+
+        // Only a trailing backslash lets a single line string continue
+        if let State::Str {
+            multi_line: false, ..
+        } = self.state
+        {
+            if !line.ends_with('\\') {
+                self.state = State::Code;
+            }
+        }
+
+        scan
+    }
+
+    // Returns how many bytes the comment opener takes, or 0 for a line
+    // comment which swallows the rest of the line.
+    fn comment_start(&mut self, bytes: &[u8], i: usize, scan: &mut LineScan) -> Option<usize> {
+        let language = self.language;
+
+        let doc_block = language
+            .doc_block_comments
+            .iter()
+            .find(|(open, close)| {
+                // `/**/` is an empty comment, not an opener of a doc block
+                is_doc_marker(bytes, i, open)
+                    && !bytes[i + open.len() - 1..].starts_with(close.as_bytes())
+            })
+            .map(|(open, close)| (*open, *close, true));
+        let block = language
+            .block_comments
+            .iter()
+            .find(|(open, _)| bytes[i..].starts_with(open.as_bytes()))
+            .map(|(open, close)| (*open, *close, false));
+
+        if let Some((open, close, doc)) = doc_block.or(block) {
+            // Nesting is tracked with the plain opener, so `/**` inside of
+            // a doc block still counts as a nested `/*`
+            let nest_open = language
+                .block_comments
+                .iter()
+                .find(|(plain, _)| open.starts_with(plain))
+                .map(|(plain, _)| *plain)
+                .unwrap_or(open);
+
+            scan.doc |= doc;
+            self.state = State::Block {
+                open: nest_open,
+                close,
+                doc,
+                depth: 1,
+            };
+            return Some(open.len());
+        }
+
+        if language
+            .doc_line_comments
+            .iter()
+            .any(|marker| is_doc_marker(bytes, i, marker))
+        {
+            scan.doc = true;
+            return Some(0);
+        }
+
+        if language
+            .line_comments
+            .iter()
+            .any(|marker| bytes[i..].starts_with(marker.as_bytes()))
+        {
+            return Some(0);
+        }
+
+        None
+    }
+
+    // Skips a single token of code starting at `i` and returns the
+    // index right after it. Opens string states when needed.
+    fn literal(&mut self, line: &str, i: usize) -> usize {
+        if self.language.is_rust() {
+            if let Some(end) = self.rust_literal(line, i) {
+                return end;
+            }
+        }
+
+        let bytes = line.as_bytes();
+        let language = self.language;
+
+        let multi_line = language
+            .multi_line_quotes
+            .iter()
+            .find(|(open, _)| bytes[i..].starts_with(open.as_bytes()))
+            .map(|quote| (quote, true));
+        let single_line = language
+            .quotes
+            .iter()
+            .find(|(open, _)| bytes[i..].starts_with(open.as_bytes()))
+            .map(|quote| (quote, false));
+
+        match multi_line.or(single_line) {
+            Some(((open, close), multi_line)) => {
+                self.state = State::Str { close, multi_line };
+                i + open.len()
+            }
+            None if is_ident_start(bytes[i]) => {
+                let mut end = i;
+                while end < bytes.len() && is_ident(bytes[end]) {
+                    end += 1;
+                }
+                end
+            }
+            None => i + 1,
+        }
+    }
+
+    fn rust_literal(&mut self, line: &str, i: usize) -> Option<usize> {
+        let bytes = line.as_bytes();
+
+        match bytes[i] {
+            b'\'' => Some(char_literal(line, i)),
+            b if is_ident_start(b) => {
+                let mut end = i;
+                while end < bytes.len() && is_ident(bytes[end]) {
+                    end += 1;
+                }
+
+                match &line[i..end] {
+                    "r" | "br" | "cr" => {
+                        let mut hashes = 0;
+                        while bytes.get(end + hashes) == Some(&b'#') {
+                            hashes += 1;
+                        }
+                        if bytes.get(end + hashes) == Some(&b'"') {
+                            self.state = State::RawStr(hashes);
+                            Some(end + hashes + 1)
+                        } else {
+                            Some(end)
+                        }
+                    }
+                    // Prefix of a byte or C string, the quote is
+                    // handled like any other one
+                    "b" | "c" if bytes.get(end) == Some(&b'"') => Some(end),
+                    "b" if bytes.get(end) == Some(&b'\'') => Some(char_literal(line, end)),
+                    _ => Some(end),
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+// `'a'`, `'\n'` and `'\u{1F600}'` are char literals, while `'a` is a
+// lifetime or a label. Returns the index right after whichever it is.
+fn char_literal(line: &str, i: usize) -> usize {
+    let bytes = line.as_bytes();
+
+    if bytes.get(i + 1) == Some(&b'\\') {
+        let mut end = i + 3;
+        while end < bytes.len() && bytes[end] != b'\'' {
+            end += 1;
+        }
+        return (end + 1).min(bytes.len());
+    }
+
+    match line[i + 1..].chars().next() {
+        Some(ch) if bytes.get(i + 1 + ch.len_utf8()) == Some(&b'\'') => i + 2 + ch.len_utf8(),
+        _ => i + 1,
+    }
+}
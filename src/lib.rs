@@ -0,0 +1,37 @@
+mod category;
+mod code_stats;
+mod config;
+mod count_lines;
+mod counter;
+mod doc_coverage;
+mod file_stats;
+mod gitignore;
+mod items;
+pub mod json;
+pub mod languages;
+mod lexer;
+mod location;
+mod module_tree;
+mod params;
+mod summary;
+mod tags;
+mod tree;
+mod unsafe_code;
+mod walk;
+mod workspace;
+
+pub use category::{Categories, Category};
+pub use code_stats::CodeStats;
+pub use config::{Config, CONFIG_FILE};
+pub use count_lines::count_lines;
+pub use counter::{Counter, Report};
+pub use doc_coverage::UndocumentedItem;
+pub use file_stats::{FileStats, SkipReason, SkippedFile, SortBy};
+pub use items::{FnKind, ItemKind, LineItems, Units};
+pub use location::Location;
+pub use params::{default_threads, Params};
+pub use summary::Summary;
+pub use tags::{TagMatch, TagMeta, DEFAULT_TAGS};
+pub use tree::DirNode;
+pub use unsafe_code::{UnsafeKind, UnsafeMatch, UnsafeStats};
+pub use workspace::{CrateStats, Member, Workspace};
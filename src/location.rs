@@ -0,0 +1,34 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use crate::file_stats::display_path;
+
+// Line of a file something was found on. Ordered by path, then line.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Location {
+    pub path: PathBuf,
+    // Starts at 1 like in editors and compiler messages
+    pub line: usize,
+}
+
+impl Location {
+    pub fn new(path: &Path, line: usize) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            line,
+        }
+    }
+
+    pub fn display_path(&self, root: &Path) -> String {
+        display_path(&self.path, root)
+    }
+}
+
+// `path:line`
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.path.display(), self.line)
+    }
+}
@@ -0,0 +1,197 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    code_stats::CodeStats,
+    count_lines::count_lines,
+    items::{parse_item, ItemKind},
+    languages::Language,
+    lexer::Lexer,
+    params::Params,
+    workspace::Workspace,
+};
+
+// Module file declared with `mod name;`
+struct Declaration {
+    name: String,
+    // Value of a `#[path = "..."]` attribute
+    path: Option<String>,
+    // Inline modules the declaration is in, outermost first
+    inline: Vec<String>,
+}
+
+// `#[path = "other.rs"]` gives `other.rs`
+fn path_attribute(code: &str) -> Option<String> {
+    let rest = code.trim_start().strip_prefix("#[path")?;
+    let rest = rest.trim_start().strip_prefix('=')?.trim_start();
+    let value = rest.strip_prefix('"')?;
+    let end = value.find('"')?;
+    Some(value[..end].to_string())
+}
+
+// Name following the `mod` keyword
+fn module_name(code: &str) -> Option<String> {
+    let mut words = code
+        .split(|ch: char| !ch.is_alphanumeric() && ch != '_')
+        .filter(|word| !word.is_empty());
+    words.find(|word| *word == "mod")?;
+    words.next().map(str::to_string)
+}
+
+// Every `mod name;` of a file. Only code is looked at, so declarations in
+// comments and strings are not followed, multi-line strings included. Blocks are followed to know the
+// inline modules a declaration is nested in.
+fn declarations(file: &Path) -> Vec<Declaration> {
+    let content = match fs::read_to_string(file) {
+        Ok(content) => content,
+        Err(e) => {
+            log::warn!("Failed to read {:?}: {}", file, e);
+            return vec![];
+        }
+    };
+
+    let mut lexer = Lexer::new(Language::from_path(file));
+    let mut declarations = vec![];
+    let mut path = None;
+    // Inline module opened by the next `{`
+    let mut pending = None;
+    // Inline modules with the depth of their blocks
+    let mut inline: Vec<(String, usize)> = vec![];
+    let mut depth = 0;
+
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() {
+            continue;
+        }
+        let scan = lexer.scan_line(line);
+        if !scan.code {
+            continue;
+        }
+        let code = &line[..scan.code_end(line)];
+
+        // Text of a string from an earlier line declares nothing, only its
+        // blocks are followed
+        if !scan.starts_in_string {
+            if let Some(value) = path_attribute(code) {
+                path = Some(value);
+            }
+            if parse_item(code) == Some(ItemKind::Mod) {
+                if let Some(name) = module_name(code) {
+                    let path = path.take();
+                    if code.trim_end().ends_with(';') {
+                        declarations.push(Declaration {
+                            name,
+                            path,
+                            inline: inline.iter().map(|(name, _)| name.clone()).collect(),
+                        });
+                    } else {
+                        pending = Some(path.unwrap_or(name));
+                    }
+                }
+            } else if !code.starts_with("#[") {
+                path = None;
+            }
+        }
+
+        for &i in &scan.symbols {
+            match line.as_bytes()[i] {
+                b'{' => {
+                    depth += 1;
+                    if let Some(name) = pending.take() {
+                        inline.push((name, depth));
+                    }
+                }
+                b'}' => {
+                    if inline.last().is_some_and(|(_, open)| *open == depth) {
+                        inline.pop();
+                    }
+                    depth = depth.saturating_sub(1);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    declarations
+}
+
+// Finds the file of a declaration in `file`, together with the directory
+// the modules it declares itself are in. `dir` is that directory of `file`.
+fn resolve(file: &Path, dir: &Path, declaration: &Declaration) -> Option<(PathBuf, PathBuf)> {
+    let base = declaration
+        .inline
+        .iter()
+        .fold(dir.to_path_buf(), |base, name| base.join(name));
+
+    if let Some(path) = &declaration.path {
+        // Relative to the file itself unless nested in an inline module
+        let child = if declaration.inline.is_empty() {
+            file.parent().unwrap_or(dir).join(path)
+        } else {
+            base.join(path)
+        };
+        let child_dir = child.parent().unwrap_or(dir).to_path_buf();
+        return child.is_file().then_some((child, child_dir));
+    }
+
+    let module_dir = base.join(&declaration.name);
+    [
+        base.join(format!("{}.rs", declaration.name)),
+        module_dir.join("mod.rs"),
+    ]
+    .into_iter()
+    .find(|child| child.is_file())
+    .map(|child| (child, module_dir))
+}
+
+// Files compiled into the crates at `roots`, following `mod` declarations
+// from the root of every target
+pub fn module_files(roots: &[PathBuf]) -> BTreeSet<PathBuf> {
+    let mut files = BTreeSet::new();
+    let mut queue = roots
+        .iter()
+        .map(|root| {
+            let dir = root.parent().unwrap_or(Path::new("")).to_path_buf();
+            (root.clone(), dir)
+        })
+        .collect::<Vec<(PathBuf, PathBuf)>>();
+
+    while let Some((file, dir)) = queue.pop() {
+        if !files.insert(file.clone()) {
+            continue;
+        }
+
+        for declaration in declarations(&file) {
+            match resolve(&file, &dir, &declaration) {
+                Some(child) => queue.push(child),
+                None => log::warn!(
+                    "Module {} declared in {:?} not found",
+                    declaration.name,
+                    file
+                ),
+            }
+        }
+    }
+
+    files
+}
+
+// Counts the files of the module trees of every crate at `params.path`
+// instead of walking the directories
+pub(crate) fn count_module_tree(params: &Params) -> CodeStats {
+    let workspace = Workspace::from_dir(&params.path);
+    let roots = workspace
+        .members
+        .iter()
+        .flat_map(|member| member.roots.iter().cloned())
+        .collect::<Vec<PathBuf>>();
+
+    let mut stats = CodeStats::new();
+    for file in module_files(&roots) {
+        count_lines(&file, params, &mut stats);
+    }
+    stats
+}
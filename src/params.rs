@@ -1,7 +1,8 @@
-use std::{env, path::PathBuf};
+use std::{num::NonZeroUsize, path::PathBuf, thread};
 
-use crate::args::Args;
+use crate::{file_stats::SortBy, tags::DEFAULT_TAGS};
 
+#[derive(Debug, Clone)]
 pub struct Params {
     pub extensions: Vec<String>,
     pub path: PathBuf,
@@ -9,39 +10,87 @@ pub struct Params {
     pub hidden: bool,
     pub docs: bool,
     pub comments: bool,
+    pub blanks: bool,
     pub fixme: bool,
     pub todo: bool,
+    pub list_tags: bool,
+    pub tags: Vec<String>,
+    // Days after which a dated tag is stale
+    pub stale: Option<u64>,
+    pub owners: bool,
     pub units: bool,
+    pub doc_coverage: bool,
+    pub categories: bool,
+    // Leaves tests, examples and benches out of every count but the categories
+    pub exclude_tests: bool,
+    pub workspace: bool,
+    // Counts the files of the crate's module tree instead of walking
+    pub module_tree: bool,
+    // Prints the unsafe counts and lists where they are
+    pub unsafe_code: bool,
     pub ratio: bool,
     pub json: bool,
+    pub languages: bool,
+    pub tree: bool,
+    pub depth: Option<usize>,
+    pub files: bool,
+    pub sort: SortBy,
+    pub threads: usize,
 }
 
-impl<'a> From<Args> for Params {
-    fn from(value: Args) -> Self {
-        let extensions: Vec<String> = match value.extensions {
-            Some(extensions) => extensions,
-            None => vec![".rs".to_string()],
-        };
-
-        let path = match value.path {
-            Some(p) => p,
-            None => env::current_dir().expect("Provided path is invalid"),
-        };
-
-        let units = extensions.iter().find(|str| *str == ".rs").is_some() && value.units;
-
+impl Params {
+    pub fn new(path: PathBuf) -> Self {
         Self {
-            extensions,
+            extensions: vec![".rs".to_string()],
             path,
-            verbose: value.verbose,
-            hidden: value.hidden,
-            docs: value.docs,
-            comments: value.comments,
-            fixme: value.fixme,
-            todo: value.todo,
-            units,
-            ratio: value.ratio,
-            json: value.json,
+            verbose: false,
+            hidden: false,
+            docs: false,
+            comments: false,
+            blanks: false,
+            fixme: false,
+            todo: false,
+            list_tags: false,
+            tags: DEFAULT_TAGS.iter().map(|tag| tag.to_string()).collect(),
+            stale: None,
+            owners: false,
+            units: false,
+            doc_coverage: false,
+            categories: false,
+            exclude_tests: false,
+            workspace: false,
+            module_tree: false,
+            unsafe_code: false,
+            ratio: false,
+            json: false,
+            languages: false,
+            tree: false,
+            depth: None,
+            files: false,
+            sort: SortBy::Path,
+            threads: default_threads(),
         }
     }
 }
+
+impl Params {
+    // Keeps the first occurrence, so the defaults stay in front
+    pub fn add_tags<I, S>(&mut self, tags: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        for tag in tags {
+            let tag = tag.into();
+            if !tag.is_empty() && !self.tags.contains(&tag) {
+                self.tags.push(tag);
+            }
+        }
+    }
+}
+
+pub fn default_threads() -> usize {
+    thread::available_parallelism()
+        .map(NonZeroUsize::get)
+        .unwrap_or(1)
+}
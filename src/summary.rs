@@ -0,0 +1,40 @@
+use crate::file_stats::FileStats;
+
+// Line counts rolled up over a group of files
+#[derive(Default, Debug, Clone, Copy)]
+pub struct Summary {
+    pub files: usize,
+    pub code: usize,
+    pub mixed: usize,
+    pub mixed_docs: usize,
+    pub comments: usize,
+    pub docs: usize,
+    pub blanks: usize,
+}
+
+impl Summary {
+    pub fn add_file(&mut self, file: &FileStats) {
+        self.files += 1;
+        self.code += file.code;
+        self.mixed += file.mixed;
+        self.mixed_docs += file.mixed_docs;
+        self.comments += file.comments;
+        self.docs += file.docs;
+        self.blanks += file.blanks;
+    }
+
+    pub fn merge(&mut self, other: &Summary) {
+        self.files += other.files;
+        self.code += other.code;
+        self.mixed += other.mixed;
+        self.mixed_docs += other.mixed_docs;
+        self.comments += other.comments;
+        self.docs += other.docs;
+        self.blanks += other.blanks;
+    }
+
+    // Mixed lines are already part of `code`
+    pub fn total(&self) -> usize {
+        self.code + self.comments + self.docs + self.blanks
+    }
+}
@@ -0,0 +1,230 @@
+use std::{
+    cmp::Reverse,
+    fmt,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{languages::Language, location::Location};
+
+// Always looked for, `--tag` and the config add more
+pub const DEFAULT_TAGS: &[&str] = &["TODO", "FIXME"];
+
+// Optional parts of `TODO(alice, #1234, 2026-03-01)`, in any order
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct TagMeta {
+    pub owner: Option<String>,
+    pub issue: Option<String>,
+    // `YYYY-MM-DD`
+    pub date: Option<String>,
+}
+
+impl TagMeta {
+    fn parse(meta: &str) -> Self {
+        let mut parsed = Self::default();
+
+        for part in meta
+            .split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+        {
+            if days_from_date(part).is_some() {
+                parsed.date.get_or_insert_with(|| part.to_string());
+            } else if is_issue(part) {
+                parsed.issue.get_or_insert_with(|| part.to_string());
+            } else {
+                let owner = part.trim_start_matches('@');
+                parsed.owner.get_or_insert_with(|| owner.to_string());
+            }
+        }
+
+        parsed
+    }
+
+    fn is_empty(&self) -> bool {
+        self.owner.is_none() && self.issue.is_none() && self.date.is_none()
+    }
+}
+
+// `#1234`, `ABC-123` or a link
+fn is_issue(part: &str) -> bool {
+    if let Some(number) = part.strip_prefix('#') {
+        return !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit());
+    }
+    if part.starts_with("http://") || part.starts_with("https://") {
+        return true;
+    }
+
+    match part.split_once('-') {
+        Some((project, number)) => {
+            !project.is_empty()
+                && project.bytes().all(|b| b.is_ascii_uppercase())
+                && !number.is_empty()
+                && number.bytes().all(|b| b.is_ascii_digit())
+        }
+        None => false,
+    }
+}
+
+// Days since 1970-01-01 of a `YYYY-MM-DD` date, following
+// http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_from_date(date: &str) -> Option<i64> {
+    let mut parts = date.split('-');
+    let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() || year.len() != 4 || month.len() != 2 || day.len() != 2 {
+        return None;
+    }
+
+    let year = year.parse::<i64>().ok()?;
+    let month = month.parse::<i64>().ok()?;
+    let day = day.parse::<i64>().ok()?;
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    };
+    if !(1..=12).contains(&month) || !(1..=days_in_month).contains(&day) {
+        return None;
+    }
+
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    Some(era * 146097 + day_of_era - 719468)
+}
+
+fn today() -> i64 {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    (seconds / 86400) as i64
+}
+
+// Dated more than `max_age` days ago. Tags without a date never get stale.
+pub fn is_stale(meta: &TagMeta, max_age: u64) -> bool {
+    meta.date
+        .as_deref()
+        .and_then(days_from_date)
+        .is_some_and(|date| today() - date > max_age as i64)
+}
+
+// Occurrence of a tag in a comment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagMatch {
+    pub location: Location,
+    pub tag: String,
+    pub meta: TagMeta,
+    pub text: String,
+    // Only set with `--stale`
+    pub stale: bool,
+}
+
+// `path:line: TAG(owner, issue, date): text`
+impl fmt::Display for TagMatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.tag)?;
+        if !self.meta.is_empty() {
+            let parts = [&self.meta.owner, &self.meta.issue, &self.meta.date]
+                .into_iter()
+                .flatten()
+                .map(String::as_str)
+                .collect::<Vec<&str>>();
+            write!(f, "({})", parts.join(", "))?;
+        }
+        if !self.text.is_empty() {
+            write!(f, ": {}", self.text)?;
+        }
+        if self.stale {
+            write!(f, " (stale)")?;
+        }
+        Ok(())
+    }
+}
+
+// Tag found in a comment, before it is tied to a file and a line
+#[derive(Debug)]
+pub struct FoundTag<'t, 'c> {
+    pub tag: &'t str,
+    pub meta: TagMeta,
+    pub text: &'c str,
+}
+
+fn is_word(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
+// Starts of the occurrences of `tag` which are whole words,
+// so neither `TODOS` nor `autoFIXME` are taken for tags
+fn word_matches<'a>(comment: &'a str, tag: &'a str) -> impl Iterator<Item = usize> + 'a {
+    comment.match_indices(tag).filter_map(move |(start, _)| {
+        let before = comment[..start].chars().next_back();
+        let after = comment[start + tag.len()..].chars().next();
+        (!before.is_some_and(is_word) && !after.is_some_and(is_word)).then_some(start)
+    })
+}
+
+// Finds every tag in a single comment, markers included, and returns it
+// with the text following it up to the next tag or the end of the comment
+pub fn find_tags<'t, 'c>(
+    comment: &'c str,
+    language: &Language,
+    tags: &'t [String],
+) -> Vec<FoundTag<'t, 'c>> {
+    let mut comment = comment.trim_end();
+    for (_, close) in language
+        .block_comments
+        .iter()
+        .chain(language.doc_block_comments)
+    {
+        if let Some(stripped) = comment.strip_suffix(close) {
+            comment = stripped;
+            break;
+        }
+    }
+
+    let mut found = tags
+        .iter()
+        .flat_map(|tag| word_matches(comment, tag).map(move |start| (start, tag.as_str())))
+        .collect::<Vec<(usize, &str)>>();
+    // Longest tag first, so `FOO-BAR` wins over `FOO` at the same start
+    found.sort_by_key(|(start, tag)| (*start, Reverse(tag.len())));
+
+    // Tags starting inside of another one are part of it
+    let mut taken_until = 0;
+    found.retain(|(start, tag)| {
+        let keep = *start >= taken_until;
+        if keep {
+            taken_until = start + tag.len();
+        }
+        keep
+    });
+
+    found
+        .iter()
+        .enumerate()
+        .map(|(i, (start, tag))| {
+            let end = found
+                .get(i + 1)
+                .map_or(comment.len(), |(next, _)| *next)
+                .max(start + tag.len());
+            let mut rest = &comment[start + tag.len()..end];
+
+            let mut meta = TagMeta::default();
+            if let Some(inner) = rest.strip_prefix('(') {
+                if let Some(close) = inner.find(')') {
+                    meta = TagMeta::parse(&inner[..close]);
+                    rest = &inner[close + 1..];
+                }
+            }
+
+            FoundTag {
+                tag,
+                meta,
+                text: rest.trim_start().trim_start_matches(':').trim(),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,86 @@
+use std::{collections::BTreeMap, path::Path};
+
+use crate::{file_stats::FileStats, summary::Summary};
+
+// Directory with the stats of every file below it rolled up
+#[derive(Default, Debug)]
+pub struct DirNode {
+    pub name: String,
+    pub summary: Summary,
+    pub children: BTreeMap<String, DirNode>,
+}
+
+impl DirNode {
+    pub fn build(root: &Path, files: &[FileStats]) -> Self {
+        let mut tree = DirNode {
+            name: root.display().to_string(),
+            ..Default::default()
+        };
+
+        for file in files {
+            let relative = file.path.strip_prefix(root).unwrap_or(&file.path);
+            let mut node = &mut tree;
+            node.summary.add_file(file);
+
+            // Last component is the file itself
+            if let Some(parent) = relative.parent() {
+                for component in parent.components() {
+                    let name = component.as_os_str().to_string_lossy().to_string();
+                    node = node
+                        .children
+                        .entry(name.clone())
+                        .or_insert_with(|| DirNode {
+                            name,
+                            ..Default::default()
+                        });
+                    node.summary.add_file(file);
+                }
+            }
+        }
+
+        tree
+    }
+
+    pub fn print(&self, max_depth: Option<usize>) {
+        let width = self.name_width(0, max_depth).max(4);
+
+        println!(
+            "{:<width$}  {:>8}  {:>8}  {:>8}  {:>8}  {:>8}  {:>8}",
+            "path", "files", "code", "comments", "docs", "blanks", "total"
+        );
+        self.print_rows(0, width, max_depth);
+    }
+
+    fn name_width(&self, depth: usize, max_depth: Option<usize>) -> usize {
+        let own = depth * 2 + self.name.len() + 1;
+        if max_depth.is_some_and(|max| depth >= max) {
+            return own;
+        }
+
+        self.children
+            .values()
+            .map(|child| child.name_width(depth + 1, max_depth))
+            .fold(own, usize::max)
+    }
+
+    fn print_rows(&self, depth: usize, width: usize, max_depth: Option<usize>) {
+        let name = format!("{}{}/", "  ".repeat(depth), self.name);
+        println!(
+            "{:<width$}  {:>8}  {:>8}  {:>8}  {:>8}  {:>8}  {:>8}",
+            name,
+            self.summary.files,
+            self.summary.code,
+            self.summary.comments,
+            self.summary.docs,
+            self.summary.blanks,
+            self.summary.total()
+        );
+
+        if max_depth.is_some_and(|max| depth >= max) {
+            return;
+        }
+        for child in self.children.values() {
+            child.print_rows(depth + 1, width, max_depth);
+        }
+    }
+}
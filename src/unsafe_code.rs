@@ -0,0 +1,79 @@
+use std::{fmt, ops::AddAssign};
+
+use crate::location::Location;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnsafeKind {
+    Block,
+    Fn,
+    Impl,
+    Trait,
+}
+
+impl UnsafeKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            UnsafeKind::Block => "block",
+            UnsafeKind::Fn => "fn",
+            UnsafeKind::Impl => "impl",
+            UnsafeKind::Trait => "trait",
+        }
+    }
+}
+
+// Uses of `unsafe` in Rust code
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnsafeStats {
+    pub blocks: usize,
+    pub fns: usize,
+    pub impls: usize,
+    pub traits: usize,
+    // Lines of code in unsafe blocks, the ones opening them included
+    pub lines: usize,
+}
+
+impl UnsafeStats {
+    pub fn add(&mut self, kind: UnsafeKind) {
+        let count = match kind {
+            UnsafeKind::Block => &mut self.blocks,
+            UnsafeKind::Fn => &mut self.fns,
+            UnsafeKind::Impl => &mut self.impls,
+            UnsafeKind::Trait => &mut self.traits,
+        };
+        *count += 1;
+    }
+
+    pub fn rows(&self) -> [(&'static str, usize); 5] {
+        [
+            ("unsafe blocks", self.blocks),
+            ("unsafe functions", self.fns),
+            ("unsafe impls", self.impls),
+            ("unsafe traits", self.traits),
+            ("lines in unsafe blocks", self.lines),
+        ]
+    }
+}
+
+impl AddAssign<&UnsafeStats> for UnsafeStats {
+    fn add_assign(&mut self, other: &UnsafeStats) {
+        self.blocks += other.blocks;
+        self.fns += other.fns;
+        self.impls += other.impls;
+        self.traits += other.traits;
+        self.lines += other.lines;
+    }
+}
+
+// Location of an unsafe block, function, impl or trait
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsafeMatch {
+    pub location: Location,
+    pub kind: UnsafeKind,
+}
+
+// `path:line: unsafe block`
+impl fmt::Display for UnsafeMatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: unsafe {}", self.location, self.kind.name())
+    }
+}
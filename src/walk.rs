@@ -0,0 +1,277 @@
+use std::{
+    collections::VecDeque,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    thread,
+};
+
+use crate::{
+    code_stats::CodeStats,
+    count_lines::count_lines,
+    file_stats::SkippedFile,
+    gitignore::{is_ignored, Gitignore},
+    params::Params,
+};
+
+// What is left to visit in a directory after hidden
+// and ignored entries are filtered out
+struct DirEntries {
+    dirs: Vec<PathBuf>,
+    files: Vec<PathBuf>,
+}
+
+// Patterns of the parent directories still apply in `dir`,
+// returns whether `dir` added its own
+fn push_gitignore(dir: &Path, ignores: &mut Vec<Arc<Gitignore>>) -> bool {
+    match Gitignore::from_dir(dir) {
+        Some(gitignore) => {
+            log::debug!("Added new Gitignore in new dir: {:?}", gitignore);
+            ignores.push(Arc::new(gitignore));
+            true
+        }
+        None => {
+            log::debug!("No gitignore in dir: {:?}", dir.file_name());
+            false
+        }
+    }
+}
+
+fn read_entries(
+    params: &Params,
+    path: &Path,
+    ignores: &[Arc<Gitignore>],
+) -> io::Result<DirEntries> {
+    let mut entries = DirEntries {
+        dirs: vec![],
+        files: vec![],
+    };
+
+    // `.` and `..` have no file name and are never hidden
+    let hidden = path
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+    if hidden && !params.hidden {
+        return Ok(entries);
+    }
+
+    for entry in fs::read_dir(path)? {
+        let entry_path = match entry {
+            Ok(entry) => entry.path(),
+            Err(e) => {
+                log::warn!("Failed to read an entry of {:?}: {}", path, e);
+                continue;
+            }
+        };
+
+        if is_ignored(ignores, &entry_path, entry_path.is_dir()) {
+            log::info!("Ignored file: {:?}", entry_path.file_name());
+            continue;
+        }
+
+        if entry_path.is_dir() {
+            log::info!("Dir name {:?}", entry_path.file_name());
+            entries.dirs.push(entry_path);
+        } else {
+            // Names which are not valid UTF-8 can still match an extension
+            let file_name = match entry_path.file_name() {
+                Some(file_name) => file_name.to_string_lossy(),
+                None => continue,
+            };
+
+            let matching_ext = params.extensions.iter().any(|ext| file_name.ends_with(ext));
+            if matching_ext {
+                log::debug!("Good file with good ext");
+                log::debug!("Filename name {:?}", file_name);
+                entries.files.push(entry_path);
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+// An unreadable directory is reported like an unreadable file
+// and the rest of the tree is still counted
+fn skip_dir(dir: &Path, e: io::Error, stats: &mut CodeStats) {
+    log::warn!("Skipped {:?}: {}", dir, e);
+    stats.add_skipped(SkippedFile {
+        path: dir.to_path_buf(),
+        reason: e.into(),
+    });
+}
+
+pub(crate) fn visit_dir(
+    params: &Params,
+    path: &Path,
+    stats: &mut CodeStats,
+    ignores: &mut Vec<Arc<Gitignore>>,
+) {
+    if path.is_dir() {
+        let has_gitignore = push_gitignore(path, ignores);
+
+        match read_entries(params, path, ignores) {
+            Ok(entries) => {
+                for file in &entries.files {
+                    count_lines(file, params, stats);
+                    log::info!("Total amount of lines: {}\n", &stats.loc());
+                }
+                for dir in &entries.dirs {
+                    visit_dir(params, dir, stats, ignores);
+                }
+            }
+            Err(e) => skip_dir(path, e, stats),
+        }
+
+        if has_gitignore {
+            ignores.pop();
+        }
+    } else {
+        // Can get here only if user provide path which is not directory
+        log::debug!("Filename name {:?}", path.file_name());
+        count_lines(path, params, stats);
+    }
+
+    log::info!("Getting out of {:?}", path.file_name());
+    log::info!("Total lines in {:?}: {}\n", path.file_name(), stats.loc());
+}
+
+struct Job {
+    dir: PathBuf,
+    ignores: Vec<Arc<Gitignore>>,
+}
+
+struct Shared<'a> {
+    params: &'a Params,
+    queues: Vec<Mutex<VecDeque<Job>>>,
+    // Jobs which are queued or being worked on
+    pending: AtomicUsize,
+    // Idle threads sleep on `wake` until jobs are pushed or none are left
+    idle: Mutex<()>,
+    wake: Condvar,
+}
+
+impl Shared<'_> {
+    // Taking the lock first means a thread between its last look at the
+    // queues and going to sleep cannot miss the notification
+    fn notify(&self) {
+        drop(self.idle.lock().unwrap());
+        self.wake.notify_all();
+    }
+}
+
+// Every thread works through its own queue of directories depth first and
+// steals from the other end of the other queues once it runs dry. Each
+// thread counts into its own `CodeStats` which are merged at the end.
+pub(crate) fn visit_dir_parallel(params: &Params, path: &Path) -> CodeStats {
+    let mut stats = CodeStats::new();
+    if params.threads <= 1 || !path.is_dir() {
+        visit_dir(params, path, &mut stats, &mut vec![]);
+        return stats;
+    }
+
+    let shared = Shared {
+        params,
+        queues: (0..params.threads).map(|_| Mutex::default()).collect(),
+        pending: AtomicUsize::new(1),
+        idle: Mutex::new(()),
+        wake: Condvar::new(),
+    };
+    shared.queues[0].lock().unwrap().push_back(Job {
+        dir: path.to_path_buf(),
+        ignores: vec![],
+    });
+
+    let results = thread::scope(|scope| {
+        let handles = (0..params.threads)
+            .map(|id| {
+                let shared = &shared;
+                scope.spawn(move || worker(id, shared))
+            })
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("Worker thread panicked"))
+            .collect::<Vec<CodeStats>>()
+    });
+
+    for result in results {
+        stats.merge(result);
+    }
+    stats
+}
+
+fn next_job(id: usize, shared: &Shared) -> Option<Job> {
+    if let Some(job) = shared.queues[id].lock().unwrap().pop_back() {
+        return Some(job);
+    }
+
+    let threads = shared.queues.len();
+    (1..threads).find_map(|offset| {
+        shared.queues[(id + offset) % threads]
+            .lock()
+            .unwrap()
+            .pop_front()
+    })
+}
+
+// Sleeps until a job can be taken, `None` once every job is done
+fn wait_for_job(id: usize, shared: &Shared) -> Option<Job> {
+    let mut idle = shared.idle.lock().unwrap();
+    loop {
+        if let Some(job) = next_job(id, shared) {
+            return Some(job);
+        }
+        if shared.pending.load(Ordering::SeqCst) == 0 {
+            return None;
+        }
+        idle = shared.wake.wait(idle).unwrap();
+    }
+}
+
+fn worker(id: usize, shared: &Shared) -> CodeStats {
+    let params = shared.params;
+    let mut stats = CodeStats::new();
+
+    loop {
+        let Some(mut job) = next_job(id, shared).or_else(|| wait_for_job(id, shared)) else {
+            break;
+        };
+
+        push_gitignore(&job.dir, &mut job.ignores);
+        match read_entries(params, &job.dir, &job.ignores) {
+            Ok(entries) => {
+                let has_dirs = !entries.dirs.is_empty();
+                shared
+                    .pending
+                    .fetch_add(entries.dirs.len(), Ordering::SeqCst);
+                let mut queue = shared.queues[id].lock().unwrap();
+                for dir in entries.dirs {
+                    queue.push_back(Job {
+                        dir,
+                        ignores: job.ignores.clone(),
+                    });
+                }
+                drop(queue);
+                if has_dirs {
+                    shared.notify();
+                }
+
+                for file in &entries.files {
+                    count_lines(file, params, &mut stats);
+                }
+            }
+            Err(e) => skip_dir(&job.dir, e, &mut stats),
+        }
+
+        if shared.pending.fetch_sub(1, Ordering::SeqCst) == 1 {
+            shared.notify();
+        }
+    }
+
+    stats
+}
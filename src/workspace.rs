@@ -0,0 +1,253 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::{file_stats::FileStats, gitignore::glob_to_regex, summary::Summary};
+
+// Directories and files of a crate with code compiled into one of its
+// targets, anything else in the crate directory is left out
+const TARGETS: &[&str] = &["src", "tests", "benches", "examples", "build.rs"];
+
+// Only the parts of `Cargo.toml` plc needs, everything else is ignored
+#[derive(Deserialize, Default, Debug)]
+struct Manifest {
+    package: Option<Package>,
+    workspace: Option<WorkspaceTable>,
+    lib: Option<Target>,
+    #[serde(default)]
+    bin: Vec<Target>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Package {
+    name: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Target {
+    name: Option<String>,
+    path: Option<PathBuf>,
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(default)]
+struct WorkspaceTable {
+    members: Vec<String>,
+    exclude: Vec<String>,
+}
+
+fn read_manifest(dir: &Path) -> Option<Manifest> {
+    let path = dir.join("Cargo.toml");
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) => {
+            log::warn!("Failed to read {:?}: {}", path, e);
+            return None;
+        }
+    };
+
+    match toml::from_str(&content) {
+        Ok(manifest) => Some(manifest),
+        Err(e) => {
+            log::warn!("Invalid manifest {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+// Directories below `root` matching a member pattern like `crates/*`
+fn expand(root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let mut dirs = vec![root.to_path_buf()];
+
+    for part in pattern
+        .split('/')
+        .filter(|part| !part.is_empty() && *part != ".")
+    {
+        if !part.contains(['*', '?', '[']) {
+            dirs = dirs.into_iter().map(|dir| dir.join(part)).collect();
+            continue;
+        }
+
+        let regex = match Regex::new(&format!("^{}$", glob_to_regex(part))) {
+            Ok(regex) => regex,
+            Err(e) => {
+                log::warn!("Invalid member pattern {:?}: {}", pattern, e);
+                return vec![];
+            }
+        };
+        dirs = dirs
+            .iter()
+            .filter_map(|dir| fs::read_dir(dir).ok())
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.is_dir()
+                    && path
+                        .file_name()
+                        .is_some_and(|name| regex.is_match(&name.to_string_lossy()))
+            })
+            .collect();
+    }
+
+    dirs.sort();
+    dirs
+}
+
+// Root files of the library and the binaries, the ones Cargo finds on
+// its own in `src` included. Files which do not exist are left out.
+fn crate_roots(dir: &Path, manifest: &Manifest) -> Vec<PathBuf> {
+    let src = dir.join("src");
+    let mut roots = vec![manifest
+        .lib
+        .as_ref()
+        .and_then(|lib| lib.path.as_ref())
+        .map_or_else(|| src.join("lib.rs"), |path| dir.join(path))];
+    roots.push(src.join("main.rs"));
+
+    for bin in &manifest.bin {
+        match (&bin.path, &bin.name) {
+            (Some(path), _) => roots.push(dir.join(path)),
+            (None, Some(name)) => {
+                roots.push(src.join("bin").join(format!("{}.rs", name)));
+                roots.push(src.join("bin").join(name).join("main.rs"));
+            }
+            (None, None) => {}
+        }
+    }
+
+    if let Ok(entries) = fs::read_dir(src.join("bin")) {
+        let mut bins = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .map(|path| {
+                if path.is_dir() {
+                    path.join("main.rs")
+                } else {
+                    path
+                }
+            })
+            .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+            .collect::<Vec<PathBuf>>();
+        bins.sort();
+        roots.extend(bins);
+    }
+
+    let mut unique = vec![];
+    for root in roots {
+        if root.is_file() && !unique.contains(&root) {
+            unique.push(root);
+        }
+    }
+    unique
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    pub name: String,
+    pub dir: PathBuf,
+    // Files the module trees of the crate's targets start from
+    pub roots: Vec<PathBuf>,
+}
+
+impl Member {
+    fn new(dir: PathBuf, manifest: &Manifest) -> Self {
+        let name = manifest
+            .package
+            .as_ref()
+            .and_then(|package| package.name.clone())
+            .or_else(|| {
+                dir.file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+            })
+            .unwrap_or_default();
+        let roots = crate_roots(&dir, manifest);
+        Self { name, dir, roots }
+    }
+
+    fn contains(&self, path: &Path) -> bool {
+        TARGETS
+            .iter()
+            .any(|target| path.starts_with(self.dir.join(target)))
+    }
+}
+
+// Crates of the Cargo workspace in a directory. A plain package is a
+// workspace with a single member, the root package of a workspace is
+// one of its members.
+#[derive(Debug, Default)]
+pub struct Workspace {
+    pub members: Vec<Member>,
+}
+
+impl Workspace {
+    pub fn from_dir(root: &Path) -> Self {
+        if !root.join("Cargo.toml").is_file() {
+            log::warn!("No Cargo.toml in {:?}", root);
+            return Self::default();
+        }
+        let Some(manifest) = read_manifest(root) else {
+            return Self::default();
+        };
+
+        let mut members = vec![];
+        if manifest.package.is_some() {
+            members.push(Member::new(root.to_path_buf(), &manifest));
+        }
+
+        if let Some(workspace) = &manifest.workspace {
+            let excluded = workspace
+                .exclude
+                .iter()
+                .flat_map(|pattern| expand(root, pattern))
+                .collect::<Vec<PathBuf>>();
+
+            for dir in workspace
+                .members
+                .iter()
+                .flat_map(|pattern| expand(root, pattern))
+            {
+                if excluded.contains(&dir)
+                    || !dir.join("Cargo.toml").is_file()
+                    || members.iter().any(|member: &Member| member.dir == dir)
+                {
+                    continue;
+                }
+                if let Some(member_manifest) = read_manifest(&dir) {
+                    members.push(Member::new(dir, &member_manifest));
+                }
+            }
+        }
+
+        Self { members }
+    }
+
+    // Files of every member crate rolled up, in the order of the members
+    pub fn crate_stats(&self, files: &[FileStats]) -> Vec<CrateStats> {
+        self.members
+            .iter()
+            .map(|member| {
+                let mut summary = Summary::default();
+                for file in files.iter().filter(|file| member.contains(&file.path)) {
+                    summary.add_file(file);
+                }
+                CrateStats {
+                    name: member.name.clone(),
+                    path: member.dir.clone(),
+                    summary,
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CrateStats {
+    pub name: String,
+    pub path: PathBuf,
+    pub summary: Summary,
+}
@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod tests {
+    use plc::Counter;
+
+    #[test]
+    fn counter_with_all_exts_and_comments() {
+        let report = Counter::new("./mock_files")
+            .extensions([".rs", ".js", ".c"])
+            .comments(true)
+            .run()
+            .expect("Should count mock files");
+
+        assert_eq!(report.stats().loc(), 66);
+        assert_eq!(report.files().len(), 3);
+        assert_eq!(report.languages()["C"].code, 13);
+    }
+
+    #[test]
+    fn counter_keeps_every_kind_of_line() {
+        let report = Counter::new("./mock_files")
+            .extensions([".rs", ".js", ".c"])
+            .run()
+            .expect("Should count mock files");
+
+        let stats = report.stats();
+        assert_eq!(stats.loc(), 46);
+        assert_eq!(stats.code(), 46);
+        assert_eq!(stats.comments(), 20);
+        assert_eq!(stats.docs(), 5);
+        assert_eq!(stats.blanks(), 13);
+        assert_eq!(stats.lines(), 84);
+    }
+
+    #[test]
+    fn counter_same_counts_with_crlf() {
+        // Same lines, one with CRLF endings and no newline at the end
+        let count = |path: &str| {
+            let report = Counter::new(path).run().expect("Should count mock file");
+            let stats = report.stats();
+            (stats.code(), stats.comments(), stats.blanks(), stats.lines())
+        };
+
+        let lf = count("./mock_projects/line_endings/lf.rs");
+        assert_eq!(lf, (3, 3, 1, 7));
+        assert_eq!(count("./mock_projects/line_endings/crlf.rs"), lf);
+    }
+
+    #[test]
+    fn counter_excludes_tests() {
+        let report = Counter::new("./mock_projects/categories")
+            .exclude_tests(true)
+            .units(true)
+            .run()
+            .expect("Should count mock project");
+
+        let stats = report.stats();
+        assert_eq!(stats.loc(), 9);
+        assert_eq!(stats.categories().test, 21);
+        assert_eq!(report.files().len(), 1);
+
+        // The TODOs, the test function and `mod tests` are test code
+        assert_eq!(stats.todo(), 0);
+        assert_eq!(stats.units().fns, 3);
+        assert_eq!(stats.units().test_fns, 0);
+        assert_eq!(stats.units().mods, 0);
+    }
+
+    #[test]
+    fn counter_units_only_with_rust() {
+        let report = Counter::new("./mock_files")
+            .extensions([".js"])
+            .units(true)
+            .run()
+            .expect("Should count mock files");
+        assert!(!report.params().units);
+
+        let report = Counter::new("./mock_files")
+            .units(true)
+            .run()
+            .expect("Should count mock files");
+        assert!(report.params().units);
+        assert_eq!(report.stats().fns(), 1);
+    }
+
+    #[test]
+    fn counter_json_report() {
+        let report = Counter::new("./mock_files")
+            .docs(true)
+            .run()
+            .expect("Should count mock files");
+
+        let json = report.json();
+        assert_eq!(json.schema_version, 1);
+        assert_eq!(json.loc, 15);
+        assert_eq!(json.total.docs, 5);
+    }
+}
@@ -138,6 +138,685 @@ mod tests {
         assert_eq!(num, 71);
     }
 
+    #[test]
+    fn with_python_comments() {
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_files")
+            .arg("-e")
+            .arg(".py")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+
+        let str = String::from_utf8_lossy(&output.stdout);
+        let num: usize = str.trim().parse().expect("Should be valid number");
+        assert_eq!(num, 6);
+    }
+
+    #[test]
+    fn with_lua_comments_and_docs() {
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_files")
+            .arg("-e")
+            .arg(".lua")
+            .arg("-c")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+
+        let str = String::from_utf8_lossy(&output.stdout);
+        let num: usize = str.trim().parse().expect("Should be valid number");
+        assert_eq!(num, 10);
+
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_files")
+            .arg("-e")
+            .arg(".lua")
+            .arg("-cd")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+
+        let str = String::from_utf8_lossy(&output.stdout);
+        let num: usize = str.trim().parse().expect("Should be valid number");
+        assert_eq!(num, 11);
+    }
+
+    #[test]
+    fn with_rust_literals() {
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_projects/lexer")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+
+        let str = String::from_utf8_lossy(&output.stdout);
+        let num: usize = str.trim().parse().expect("Should be valid number");
+        assert_eq!(num, 16);
+
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_projects/lexer")
+            .arg("-cd")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+
+        let str = String::from_utf8_lossy(&output.stdout);
+        let num: usize = str.trim().parse().expect("Should be valid number");
+        assert_eq!(num, 23);
+    }
+
+    #[test]
+    fn with_nested_and_mid_line_block_comments() {
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_projects/block_comments")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+
+        // Rust block comments nest
+        let str = String::from_utf8_lossy(&output.stdout);
+        let num: usize = str.trim().parse().expect("Should be valid number");
+        assert_eq!(num, 5);
+
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_projects/block_comments")
+            .arg("-e")
+            .arg(".c")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+
+        // C block comments do not
+        let str = String::from_utf8_lossy(&output.stdout);
+        let num: usize = str.trim().parse().expect("Should be valid number");
+        assert_eq!(num, 6);
+    }
+
+    #[test]
+    fn with_files_sorted_by_code() {
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_files")
+            .arg("-e")
+            .arg(".rs")
+            .arg(".js")
+            .arg(".c")
+            .arg("--files")
+            .arg("--sort")
+            .arg("code")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+
+        let str = String::from_utf8_lossy(&output.stdout);
+        let mut lines = str.lines();
+        assert_eq!(lines.next(), Some("46"));
+        assert_eq!(lines.next(), Some(""));
+        assert!(lines.next().unwrap().starts_with("path"));
+
+        let rows = lines
+            .map(|line| line.split_whitespace().collect::<Vec<&str>>())
+            .collect::<Vec<Vec<&str>>>();
+        assert_eq!(
+            rows,
+            vec![
+                vec!["some_js_code.js", "JavaScript", "23", "0", "8", "0", "8"],
+                vec!["some_c_code.c", "C", "13", "0", "7", "0", "5"],
+                vec!["ratio_file.rs", "Rust", "10", "0", "5", "5", "0"],
+            ]
+        );
+    }
+
+    #[test]
+    fn with_files_json() {
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_files")
+            .arg("--files")
+            .arg("-j")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+
+        let json: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("Should be valid json");
+        assert_eq!(
+            json["files"],
+            serde_json::json!([{
+                "path": "ratio_file.rs",
+                "language": "Rust",
+                "code": 10,
+                "mixed": 0,
+                "mixed_docs": 0,
+                "comments": 5,
+                "docs": 5,
+                "blanks": 0,
+                "units": {
+                    "structs": 0,
+                    "enums": 0,
+                    "unions": 0,
+                    "traits": 0,
+                    "type_aliases": 0,
+                    "functions": 0,
+                    "free_functions": 0,
+                    "methods": 0,
+                    "trait_impl_methods": 0,
+                    "required_methods": 0,
+                    "provided_methods": 0,
+                    "test_functions": 0,
+                    "closures": 0,
+                    "impl_blocks": 0,
+                    "modules": 0,
+                    "consts": 0,
+                    "statics": 0,
+                    "extern_blocks": 0,
+                    "macros": 0
+                }
+            }])
+        );
+    }
+
+    #[test]
+    fn with_json_schema() {
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_files")
+            .arg("-rdc")
+            .arg("-j")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+
+        let json: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("Should be valid json");
+        assert_eq!(json["schema_version"], 1);
+        assert_eq!(json["loc"], 20);
+        assert_eq!(json["todo"], 0);
+        assert_eq!(json["units"]["functions"], 0);
+        assert_eq!(
+            json["ratio"],
+            serde_json::json!({"loc": 50.0, "comments": 25.0, "docs": 25.0, "blanks": 0.0})
+        );
+        assert_eq!(json["languages"][0]["language"], "Rust");
+        assert_eq!(json["total"]["total"], 20);
+        assert_eq!(json["files"], serde_json::json!([]));
+        assert_eq!(json["tree"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn with_languages_table() {
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_files")
+            .arg("-e")
+            .arg(".rs")
+            .arg(".js")
+            .arg(".c")
+            .arg("-l")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+
+        let str = String::from_utf8_lossy(&output.stdout);
+        let rows = str
+            .lines()
+            .map(|line| line.split_whitespace().collect::<Vec<&str>>())
+            .filter(|row| row.len() == 7)
+            .collect::<Vec<Vec<&str>>>();
+        assert_eq!(
+            rows,
+            vec![
+                vec!["language", "files", "code", "comments", "docs", "blanks", "total"],
+                vec!["C", "1", "13", "7", "0", "5", "25"],
+                vec!["JavaScript", "1", "23", "8", "0", "8", "39"],
+                vec!["Rust", "1", "10", "5", "5", "0", "20"],
+                vec!["Total", "3", "46", "20", "5", "13", "84"],
+            ]
+        );
+    }
+
+    #[test]
+    fn with_languages_by_file_name() {
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_projects/filenames")
+            .arg("-e")
+            .arg("Makefile")
+            .arg("Dockerfile")
+            .arg("-l")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+
+        // `# comment` lines are comments, not plain text
+        let str = String::from_utf8_lossy(&output.stdout);
+        let rows = str
+            .lines()
+            .map(|line| line.split_whitespace().collect::<Vec<&str>>())
+            .filter(|row| row.len() == 7)
+            .collect::<Vec<Vec<&str>>>();
+        assert_eq!(
+            rows,
+            vec![
+                vec!["language", "files", "code", "comments", "docs", "blanks", "total"],
+                vec!["Dockerfile", "1", "2", "1", "0", "0", "3"],
+                vec!["Makefile", "1", "3", "1", "0", "1", "5"],
+                vec!["Total", "2", "5", "2", "0", "1", "8"],
+            ]
+        );
+    }
+
+    #[test]
+    fn with_tree() {
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_files")
+            .arg("-a")
+            .arg("-e")
+            .arg(".rs")
+            .arg(".js")
+            .arg(".c")
+            .arg("--tree")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+
+        let str = String::from_utf8_lossy(&output.stdout);
+        let rows = str
+            .lines()
+            .map(|line| line.split_whitespace().collect::<Vec<&str>>())
+            .filter(|row| row.len() == 7)
+            .collect::<Vec<Vec<&str>>>();
+        assert_eq!(
+            rows,
+            vec![
+                vec!["path", "files", "code", "comments", "docs", "blanks", "total"],
+                vec!["./mock_files/", "4", "52", "24", "10", "13", "99"],
+                vec![".hidden_dir/", "1", "6", "4", "5", "0", "15"],
+            ]
+        );
+
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_files")
+            .arg("-a")
+            .arg("--tree")
+            .arg("--depth")
+            .arg("0")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+
+        let str = String::from_utf8_lossy(&output.stdout);
+        assert!(str.contains("./mock_files/"));
+        assert!(!str.contains(".hidden_dir/"));
+    }
+
+    #[test]
+    fn with_gitignore_rules() {
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_projects/gitignore")
+            .arg("--files")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+
+        let str = String::from_utf8_lossy(&output.stdout);
+        let mut lines = str.lines();
+        assert_eq!(lines.next(), Some("6"));
+
+        let paths = lines
+            .skip(2)
+            .filter_map(|line| line.split_whitespace().next())
+            .collect::<Vec<&str>>();
+        assert_eq!(
+            paths,
+            vec![
+                "main.rs",
+                "old_keep.rs",
+                "other/local.rs",
+                "output.rs",
+                "sub/build/b.rs",
+                "sub/old_sub.rs",
+            ]
+        );
+    }
+
+    #[test]
+    fn with_threads_same_as_sequential() {
+        let run = |threads: &str| {
+            let output = Command::new("cargo")
+                .arg("run")
+                .arg("--")
+                .arg("-p")
+                .arg("./mock_projects")
+                .arg("-a")
+                .arg("-e")
+                .arg(".rs")
+                .arg(".c")
+                .arg("-u")
+                .arg("--files")
+                .arg("--tree")
+                .arg("-j")
+                .arg("--threads")
+                .arg(threads)
+                .output()
+                .expect("");
+            assert!(output.status.success());
+            String::from_utf8_lossy(&output.stdout).to_string()
+        };
+
+        let sequential = run("1");
+        assert_eq!(run("4"), sequential);
+        assert_eq!(run("16"), sequential);
+    }
+
+    #[test]
+    fn with_mixed_lines_and_trailing_tags() {
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_projects/tags")
+            .arg("-t")
+            .arg("-f")
+            .arg("-j")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+
+        // Tags in strings and identifiers are not counted
+        let json: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("Should be valid json");
+        assert_eq!(json["todo"], 3);
+        assert_eq!(json["fixme"], 2);
+        assert_eq!(json["total"]["code"], 8);
+        assert_eq!(json["total"]["mixed"], 4);
+        assert_eq!(json["total"]["mixed_docs"], 1);
+        assert_eq!(json["total"]["comments"], 1);
+        assert_eq!(json["total"]["docs"], 1);
+    }
+
+    #[test]
+    fn with_listed_tags() {
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_projects/tags")
+            .arg("--list-tags")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "8\n\n\
+             ./mock_projects/tags/mixed.rs:1: TODO: split into modules\n\
+             ./mock_projects/tags/mixed.rs:3: FIXME: use a set\n\
+             ./mock_projects/tags/mixed.rs:4: TODO: remove\n\
+             ./mock_projects/tags/mixed.rs:5: TODO: inline\n\
+             ./mock_projects/tags/mixed.rs:10: FIXME: this is documented\n"
+        );
+
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_projects/tags")
+            .arg("--list-tags")
+            .arg("-j")
+            .output()
+            .expect("");
+        let json: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("Should be valid json");
+        assert_eq!(
+            json["tags"][1],
+            serde_json::json!({
+                "path": "mixed.rs",
+                "line": 3,
+                "tag": "FIXME",
+                "owner": null,
+                "issue": null,
+                "date": null,
+                "text": "use a set",
+                "stale": false
+            })
+        );
+    }
+
+    #[test]
+    fn with_custom_tags() {
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_projects/custom_tags")
+            .arg("-t")
+            .arg("--tag")
+            .arg("XXX")
+            .arg("--tag")
+            .arg("NOTE")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+
+        // HACK and SAFETY come from the .plc.toml of the project
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "4\ntodo: 1\nxxx: 1\nnote: 1\nhack: 2\nsafety: 1\n"
+        );
+
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_projects/custom_tags")
+            .arg("-j")
+            .output()
+            .expect("");
+        let json: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("Should be valid json");
+        assert_eq!(
+            json["tag_counts"],
+            serde_json::json!({"TODO": 1, "FIXME": 0, "HACK": 2, "SAFETY": 1})
+        );
+    }
+
+    #[test]
+    fn with_overlapping_tags() {
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_projects/overlapping_tags")
+            .arg("--tag")
+            .arg("FOO")
+            .arg("--tag")
+            .arg("FOO-BAR")
+            .arg("--tag")
+            .arg("BAR")
+            .arg("--list-tags")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+
+        // The longest tag wins, BAR inside of FOO-BAR is no tag of its own
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "2\nfoo: 1\nfoo-bar: 1\nbar: 0\n\n\
+             ./mock_projects/overlapping_tags/main.rs:2: FOO-BAR: x\n\
+             ./mock_projects/overlapping_tags/main.rs:3: FOO: y\n"
+        );
+    }
+
+    #[test]
+    fn with_tag_owners_and_stale_dates() {
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_projects/tag_meta")
+            .arg("--stale")
+            .arg("30")
+            .arg("--owners")
+            .arg("--list-tags")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+
+        // 2021-02-30 is not a date, so that TODO never gets stale
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "3\nstale: 1\n\n\
+             owner      tags\n\
+             alice         2\n\
+             bob           1\n\
+             carol         1\n\n\
+             ./mock_projects/tag_meta/meta.rs:1: TODO(alice, #1234, 2020-03-01): drop the legacy parser (stale)\n\
+             ./mock_projects/tag_meta/meta.rs:2: TODO(bob, 2999-01-01): revisit after the rewrite\n\
+             ./mock_projects/tag_meta/meta.rs:4: FIXME(alice, PLC-42): overflow on 32 bit\n\
+             ./mock_projects/tag_meta/meta.rs:5: TODO: no metadata at all\n\
+             ./mock_projects/tag_meta/meta.rs:6: TODO(carol, https://example.com/issues/7): odd date\n"
+        );
+
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_projects/tag_meta")
+            .arg("--list-tags")
+            .arg("-j")
+            .output()
+            .expect("");
+        let json: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("Should be valid json");
+        assert_eq!(
+            json["owners"],
+            serde_json::json!({"alice": 2, "bob": 1, "carol": 1})
+        );
+        assert_eq!(json["stale"], 0);
+        assert_eq!(
+            json["tags"][2],
+            serde_json::json!({
+                "path": "meta.rs",
+                "line": 4,
+                "tag": "FIXME",
+                "owner": "alice",
+                "issue": "PLC-42",
+                "date": null,
+                "text": "overflow on 32 bit",
+                "stale": false
+            })
+        );
+    }
+
+    #[test]
+    fn with_blanks_and_physical_lines() {
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_files")
+            .arg("-e")
+            .arg(".rs")
+            .arg(".js")
+            .arg(".c")
+            .arg("-b")
+            .arg("-c")
+            .arg("-d")
+            .arg("-r")
+            .output()
+            .expect("");
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "84\nblanks: 13\nlines: 84\ncomments: 23.8%\ndocs: 6.0%\nblanks: 15.5%\nloc: 54.8%\n"
+        );
+
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_files")
+            .arg("-e")
+            .arg(".rs")
+            .arg(".js")
+            .arg(".c")
+            .arg("-j")
+            .output()
+            .expect("");
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(report["loc"], 46);
+        assert_eq!(report["blanks"], 13);
+        assert_eq!(report["lines"], 84);
+    }
+
+    #[test]
+    fn with_binary_and_non_utf8_files() {
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_projects/encodings")
+            .arg("-c")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "7\n");
+        assert!(String::from_utf8_lossy(&output.stderr)
+            .contains("Skipped files (1):\n  ./mock_projects/encodings/binary.rs: binary file\n"));
+
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_projects/encodings")
+            .arg("-j")
+            .output()
+            .expect("");
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(report["skipped"][0]["path"], "binary.rs");
+        assert_eq!(report["skipped"][0]["reason"], "binary file");
+    }
+
     #[test]
     fn with_hidden_only_rust() {
         let mut child = Command::new("cargo")
@@ -193,4 +872,305 @@ mod tests {
 
         assert_eq!(output, expected_str);
     }
+
+    #[test]
+    fn with_every_kind_of_item() {
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_projects/items")
+            .arg("-u")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+
+        // Functions in the trait, the extern block and the impls are counted too,
+        // the ones in the raw string fixture are not
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "54\nstructs: 1\nenums: 1\nunions: 1\ntraits: 1\ntype aliases: 1\nfunctions: 7\n\
+             free functions: 4\nmethods: 0\ntrait impl methods: 2\nrequired methods: 1\n\
+             provided methods: 0\ntest functions: 0\nclosures: 0\n\
+             impl blocks: 3\nmodules: 1\nconsts: 2\nstatics: 2\nextern blocks: 1\nmacros: 1\n"
+        );
+    }
+
+    #[test]
+    fn with_items_only_in_rust_files() {
+        let units = |extensions: &[&str]| {
+            let output = Command::new("cargo")
+                .arg("run")
+                .arg("--")
+                .arg("-p")
+                .arg("./mock_projects/items")
+                .arg("-e")
+                .args(extensions)
+                .arg("-u")
+                .arg("-j")
+                .output()
+                .expect("");
+            assert!(output.status.success());
+
+            let json: serde_json::Value =
+                serde_json::from_slice(&output.stdout).expect("Should be valid json");
+            json["units"].clone()
+        };
+
+        // The static, struct, union and enum of the C file are no units
+        assert_eq!(units(&[".rs", ".c"]), units(&[".rs"]));
+    }
+
+    #[test]
+    fn with_kinds_of_functions() {
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_projects/functions")
+            .arg("-u")
+            .arg("-j")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+
+        // Braces in strings and comments do not open blocks,
+        // or-patterns and `||` between conditions are no closures.
+        // `for` in a comment makes no trait impl, `for` on the next line does.
+        let json: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("Should be valid json");
+        let units = &json["units"];
+        assert_eq!(units["functions"], 13);
+        assert_eq!(units["free_functions"], 1);
+        assert_eq!(units["methods"], 4);
+        assert_eq!(units["trait_impl_methods"], 3);
+        assert_eq!(units["required_methods"], 2);
+        assert_eq!(units["provided_methods"], 1);
+        assert_eq!(units["test_functions"], 2);
+        assert_eq!(units["closures"], 4);
+    }
+
+    #[test]
+    fn with_doc_coverage() {
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_projects/doc_coverage")
+            .arg("--doc-coverage")
+            .arg("-j")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+
+        // Inner docs, private and `pub(crate)` items are left out
+        let json: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("Should be valid json");
+        let coverage = &json["doc_coverage"];
+        // Items in the strings of EXAMPLE and RAW_EXAMPLE are left out
+        assert_eq!(coverage["public_items"], 10);
+        assert_eq!(coverage["documented_items"], 7);
+        assert_eq!(coverage["coverage"], 70.0);
+        assert_eq!(
+            coverage["files"][1],
+            serde_json::json!({
+                "path": "util.rs",
+                "public_items": 2,
+                "documented_items": 1,
+                "coverage": 50.0
+            })
+        );
+        assert_eq!(
+            coverage["undocumented"],
+            serde_json::json!([
+                {"path": "lib.rs", "line": 5, "kind": "struct", "name": "Undocumented"},
+                {"path": "lib.rs", "line": 20, "kind": "fn", "name": "is_origin"},
+                {"path": "util.rs", "line": 1, "kind": "static", "name": "COUNTER"}
+            ])
+        );
+    }
+
+    #[test]
+    fn with_categories_and_excluded_tests() {
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_projects/categories")
+            .arg("--categories")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+
+        // `#[test]` functions and the `#[cfg(test)]` items of src are test code
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "34\nproduction: 9\ntest: 21\nexample: 3\nbench: 1\n"
+        );
+
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_projects/categories")
+            .arg("--exclude-tests")
+            .arg("-b")
+            .arg("-j")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+
+        let json: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("Should be valid json");
+        assert_eq!(json["loc"], 14);
+        assert_eq!(json["total"]["files"], 1);
+        assert_eq!(
+            json["categories"],
+            serde_json::json!({"production": 9, "test": 21, "example": 3, "bench": 1})
+        );
+    }
+
+    #[test]
+    fn with_workspace_crates() {
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_projects/workspace")
+            .arg("--workspace")
+            .arg("-j")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+
+        // Excluded crates, directories without a manifest and files
+        // outside of the targets are not part of the workspace
+        let json: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("Should be valid json");
+        assert_eq!(json["loc"], 20);
+        let workspace = &json["workspace"];
+        assert_eq!(workspace["crates"][0]["name"], "app");
+        assert_eq!(workspace["crates"][0]["path"], ".");
+        assert_eq!(workspace["crates"][0]["files"], 2);
+        assert_eq!(
+            workspace["crates"][1],
+            serde_json::json!({
+                "name": "core",
+                "path": "crates/core",
+                "files": 4,
+                "code": 11,
+                "mixed": 0,
+                "mixed_docs": 0,
+                "comments": 0,
+                "docs": 1,
+                "blanks": 0,
+                "total": 12
+            })
+        );
+        assert_eq!(workspace["crates"][2]["name"], "cli");
+        assert_eq!(workspace["crates"].as_array().map(Vec::len), Some(3));
+        assert_eq!(workspace["total"]["code"], 17);
+        assert_eq!(workspace["total"]["total"], 19);
+    }
+
+    #[test]
+    fn with_module_tree() {
+        let output = Command::new("cargo")
+            .arg("run")
+            .arg("--")
+            .arg("-p")
+            .arg("./mock_projects/module_tree")
+            .arg("--module-tree")
+            .arg("--files")
+            .arg("-j")
+            .output()
+            .expect("");
+        assert!(output.status.success());
+
+        // Stray files and modules only mentioned in comments
+        // or strings are not compiled, so they are not counted
+        let json: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("Should be valid json");
+        assert_eq!(json["loc"], 20);
+        let paths = json["files"]
+            .as_array()
+            .expect("Should list files")
+            .iter()
+            .map(|file| file["path"].as_str().unwrap_or_default())
+            .collect::<Vec<&str>>();
+        assert_eq!(
+            paths,
+            [
+                "src/a/child.rs",
+                "src/a.rs",
+                "src/b/mod.rs",
+                "src/bin/extra.rs",
+                "src/inline/nested.rs",
+                "src/lib.rs",
+                "src/main.rs",
+                "src/other/c_impl.rs",
+                "tools/run.rs"
+            ]
+        );
+    }
+
+    #[test]
+    fn with_unsafe_code() {
+        let unsafe_json = |exclude_tests: bool| {
+            let mut command = Command::new("cargo");
+            command
+                .arg("run")
+                .arg("--")
+                .arg("-p")
+                .arg("./mock_projects/unsafe_code")
+                .arg("--unsafe")
+                .arg("-j");
+            if exclude_tests {
+                command.arg("--exclude-tests");
+            }
+            let output = command.output().expect("");
+            assert!(output.status.success());
+
+            let json: serde_json::Value =
+                serde_json::from_slice(&output.stdout).expect("Should be valid json");
+            json["unsafe"].clone()
+        };
+
+        // `unsafe {` in comments and strings is no block, comment
+        // lines in a block are no lines of unsafe code
+        let locations = serde_json::json!([
+            {"path": "ffi.rs", "line": 3, "kind": "trait"},
+            {"path": "ffi.rs", "line": 5, "kind": "impl"},
+            {"path": "ffi.rs", "line": 7, "kind": "fn"},
+            {"path": "ffi.rs", "line": 11, "kind": "fn"},
+            {"path": "ffi.rs", "line": 16, "kind": "block"},
+            {"path": "ffi.rs", "line": 21, "kind": "block"}
+        ]);
+        let mut all_locations = locations.as_array().unwrap().clone();
+        all_locations.push(serde_json::json!({"path": "tests/it.rs", "line": 6, "kind": "block"}));
+        assert_eq!(
+            unsafe_json(false),
+            serde_json::json!({
+                "blocks": 3,
+                "functions": 2,
+                "impls": 1,
+                "traits": 1,
+                "lines": 8,
+                "locations": all_locations
+            })
+        );
+
+        // The block in the integration test is left out of the list too
+        assert_eq!(
+            unsafe_json(true),
+            serde_json::json!({
+                "blocks": 2,
+                "functions": 2,
+                "impls": 1,
+                "traits": 1,
+                "lines": 7,
+                "locations": locations
+            })
+        );
+    }
 }